@@ -6,7 +6,10 @@ import {
   CachingYahooFinanceStockScrapper,
 } from "./scrapper/caching.ts";
 import * as market_stack from "./scrapper/market-stack.ts";
-import { ProductMetric } from "../../json-schema/typescript/index.ts";
+import {
+  AssetType,
+  ProductMetric,
+} from "../../json-schema/typescript/index.ts";
 import { associateBy } from "@std/collections";
 import { Region } from "./scrapper/ishares.ts";
 
@@ -54,6 +57,11 @@ export class Stock extends Product {
     return {
       ticker: this.ticker,
       name: marketStackMetric.name,
+      asset_type: AssetType.Stock,
+      exchange: marketStackMetric.exchange,
+      currency: marketStackMetric.currency,
+      price: yahooMetric.latestPrice,
+      as_of: yahooMetric.scrappedAt,
       long_term_total_return: yahooMetric.longTermTotalReturn,
       one_month_price_change: oneMonthPriceChange,
     };
@@ -89,6 +97,12 @@ export class Etf extends Product {
     return {
       ticker: this.ticker,
       name: marketStackMetric.name,
+      asset_type: AssetType.ExchangeTradedFund,
+      exchange: marketStackMetric.exchange,
+      currency: marketStackMetric.currency,
+      region: this.isharesRegion.code,
+      price: yahooMetric.latestPrice,
+      as_of: yahooMetric.scrappedAt,
       long_term_total_return: isharesMetric.longTermTotalReturn,
       one_month_price_change: oneMonthPriceChange,
     };
@@ -18,6 +18,8 @@ export async function scrapProducts(
       ticker: product.symbol,
       name: product.name,
       price1MonthAgo: product.close,
+      exchange: product.exchange,
+      currency: product.price_currency,
     };
   });
 }
@@ -26,6 +28,8 @@ export interface ProductMetric {
   ticker: string;
   name: string;
   price1MonthAgo: number;
+  exchange?: string;
+  currency?: string;
 }
 
 interface MarketStackResponse {
@@ -36,4 +40,6 @@ interface MarketStackProduct {
   close: number;
   name: string;
   symbol: string;
+  exchange?: string;
+  price_currency?: string;
 }
@@ -10,12 +10,15 @@ export async function scrapStock(
   return {
     longTermTotalReturn: await scrapLongTermTotalReturn(page.page),
     latestPrice: await scrapLatestPrice(page.page),
+    scrappedAt: Temporal.Now.instant().toString(),
   };
 }
 
 export interface StockMetric {
   longTermTotalReturn: number;
   latestPrice: number;
+  /** When the page was scraped, in RFC 3339. Absent from results cached before it was recorded. */
+  scrappedAt?: string;
 }
 
 export async function scrapEtf(
@@ -25,11 +28,14 @@ export async function scrapEtf(
   await page.goto(url(ticker));
   return {
     latestPrice: await scrapLatestPrice(page.page),
+    scrappedAt: Temporal.Now.instant().toString(),
   };
 }
 
 export interface EtfMetric {
   latestPrice: number;
+  /** When the page was scraped, in RFC 3339. Absent from results cached before it was recorded. */
+  scrappedAt?: string;
 }
 
 function url(ticker: string): string {
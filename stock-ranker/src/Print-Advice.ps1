@@ -1 +0,0 @@
-ConvertFrom-Json | Select-Object ticker, ratio | Format-Table
@@ -1,43 +1,1047 @@
 use crate::arithmetic_renderer::ArithmeticRenderer;
+use crate::config::Allocation;
+use crate::config::Caps;
+use crate::ordering::StockOrdering;
 use crate::ranker::Score;
 use crate::ranker::Ticker;
-use itertools::Itertools;
+use crate::scoring_candidate::ScoringCandidates;
+use schema::Holding;
+use schema::Rebalance;
 use schema::StockAdvice;
+use schema::Trade;
+use schema::TradeAction;
 use std::collections::HashMap;
 
+/// Slack for floating point errors when comparing ratios with their caps or counting shares.
+const TOLERANCE: f64 = 1e-9;
+
+/// Smallest fraction of a share that can be bought when fractional shares are allowed.
+const FRACTIONAL_SHARE_STEP: f64 = 1e-4;
+
+/// Cash to invest and the prices to buy shares with.
+pub struct Budget {
+    pub cash: f64,
+
+    /// Tickers without a positive price get an amount but no quantity.
+    pub prices: HashMap<Ticker, f64>,
+
+    pub fractional_shares: bool,
+}
+
+/// What is already held, to rebalance along with the cash.
+pub struct Portfolio {
+    pub holdings: Vec<Holding>,
+
+    /// Only spend the cash toward the ratios, never selling.
+    pub buy_only: bool,
+}
+
+/// Advice for each ticker to invest in, with the cash left if a [Budget] is given.
+pub struct Advice {
+    pub entries: Vec<StockAdvice>,
+
+    /// Unrounded ratio of cash of each ticker in the entries.
+    pub ratios: HashMap<Ticker, f64>,
+
+    pub leftover_cash: Option<String>,
+}
+
+/// Tickers in the investment window, with the ratios of cash they get and may get at most.
+struct Picks {
+    tickers: Vec<Ticker>,
+    ratios: Vec<f64>,
+    max_ratios: Vec<f64>,
+}
+
+/// Cash spent on a ticker, and the shares it buys if the price is known.
+struct Purchase {
+    amount: f64,
+    quantity: Option<f64>,
+
+    /// Ratio of all cash that the amount actually is, after rounding the quantity.
+    effective_ratio: f64,
+}
+
+/// Rounding up the quantity of a ticker by a lot.
+struct RoundUp {
+    index: usize,
+    cost: f64,
+
+    /// How much closer the amount gets to its target.
+    improvement: f64,
+}
+
+impl RoundUp {
+    fn density(&self) -> f64 {
+        self.improvement / self.cost
+    }
+}
+
+/// State of [InvestAdvisor::round_up] while visiting the combinations.
+struct RoundUpSearch<'a> {
+    round_ups: &'a [RoundUp],
+
+    /// Positions in `round_ups` by decreasing improvement per cost.
+    by_density: Vec<usize>,
+
+    chosen: Vec<usize>,
+    best: Vec<usize>,
+    best_improvement: f64,
+}
+
+impl RoundUpSearch<'_> {
+    /// Decides whether to round up from the `next` position on, with the cash left and the
+    /// improvement of those chosen so far.
+    fn visit(&mut self, next: usize, cash: f64, improvement: f64) {
+        if improvement > self.best_improvement + TOLERANCE {
+            self.best_improvement = improvement;
+            self.best = self.chosen.clone();
+        }
+        if next == self.round_ups.len()
+            || improvement + self.bound(next, cash) <= self.best_improvement + TOLERANCE
+        {
+            return;
+        }
+        let round_up = &self.round_ups[next];
+        if round_up.cost <= cash + TOLERANCE {
+            self.chosen.push(round_up.index);
+            self.visit(
+                next + 1,
+                cash - round_up.cost,
+                improvement + round_up.improvement,
+            );
+            self.chosen.pop();
+        }
+        self.visit(next + 1, cash, improvement);
+    }
+
+    /// Greatest improvement from the `next` position on if lots could be split.
+    fn bound(&self, next: usize, mut cash: f64) -> f64 {
+        let mut improvement = 0.0;
+        for round_up in self.by_density.iter().filter(|&&i| i >= next) {
+            let round_up = &self.round_ups[*round_up];
+            if round_up.cost <= cash {
+                cash -= round_up.cost;
+                improvement += round_up.improvement;
+            } else {
+                return improvement + round_up.improvement * cash.max(0.0) / round_up.cost;
+            }
+        }
+        improvement
+    }
+}
+
 #[derive(Default)]
 pub struct InvestAdvisor {
     arithmetic_renderer: ArithmeticRenderer,
+    ordering: StockOrdering,
+    allocation: Allocation,
+    caps: Caps,
 }
 
 impl InvestAdvisor {
+    pub fn new(
+        ordering: StockOrdering,
+        allocation: Allocation,
+        caps: Caps,
+    ) -> anyhow::Result<Self> {
+        if let Allocation::Softmax { temperature } = allocation
+            && !(temperature.is_finite() && temperature > 0.0)
+        {
+            anyhow::bail!(
+                "Softmax temperature {} is not a positive number",
+                temperature
+            )
+        }
+        let advisor = Self {
+            arithmetic_renderer: Default::default(),
+            ordering,
+            allocation,
+            caps,
+        };
+        let tickers = advisor.caps.tickers.keys().map(String::as_str);
+        for ticker in [None].into_iter().chain(tickers.map(Some)) {
+            let (min, max) = advisor.bounds(ticker);
+            if !(0.0..=1.0).contains(&min) || !(0.0..=1.0).contains(&max) || min > max {
+                anyhow::bail!(
+                    "Caps {}% to {}%{} are not within 0% to 100% in order",
+                    min * 100.0,
+                    max * 100.0,
+                    ticker.map_or_else(String::default, |t| format!(" of {}", t))
+                )
+            }
+        }
+        Ok(advisor)
+    }
+
     pub fn render_advice(
         &self,
+        candidates: &ScoringCandidates,
         scores: &HashMap<Ticker, Score>,
         skip_count: usize,
         invest_num: usize,
-    ) -> Vec<StockAdvice> {
-        let candidates: Vec<_> = scores
+        budget: Option<&Budget>,
+    ) -> anyhow::Result<Advice> {
+        let picks = self.pick(candidates, scores, skip_count, invest_num)?;
+        let ratios = picks
+            .tickers
             .iter()
-            .sorted_unstable_by(|(_, score_a), (_, score_b)| {
-                score_b.value.total_cmp(&score_a.value)
-            })
+            .cloned()
+            .zip(picks.ratios.iter().copied())
+            .collect();
+        let Some(budget) = budget else {
+            let entries = picks
+                .tickers
+                .iter()
+                .zip(picks.ratios)
+                .map(|(ticker, ratio)| self.build_entry(ticker, ratio, None))
+                .collect();
+            return Ok(Advice {
+                entries,
+                ratios,
+                leftover_cash: None,
+            });
+        };
+
+        let purchases = self.purchase(&picks.tickers, &picks.ratios, &picks.max_ratios, budget);
+        let spent: f64 = purchases.iter().map(|purchase| purchase.amount).sum();
+        let entries = picks
+            .tickers
+            .iter()
+            .zip(picks.ratios)
+            .zip(purchases)
+            .map(|((ticker, ratio), purchase)| self.build_entry(ticker, ratio, Some(purchase)))
+            .collect();
+        Ok(Advice {
+            entries,
+            ratios,
+            leftover_cash: Some(
+                self.arithmetic_renderer
+                    .render_float((budget.cash - spent).max(0.0)),
+            ),
+        })
+    }
+
+    /// Picks the tickers in the investment window and splits the cash among them.
+    fn pick(
+        &self,
+        candidates: &ScoringCandidates,
+        scores: &HashMap<Ticker, Score>,
+        skip_count: usize,
+        invest_num: usize,
+    ) -> anyhow::Result<Picks> {
+        let candidates: Vec<_> = self
+            .ordering
+            .sort(candidates, scores)
+            .into_iter()
             .skip(skip_count)
             .take(invest_num)
             .collect();
-        let total_score = candidates.iter().map(|(_, score)| score.value).sum();
-        candidates
-            .into_iter()
-            .map(|(ticker, score)| self.build_entry(ticker, score, total_score))
+        let scores: Vec<_> = candidates.iter().map(|(_, score)| score.value).collect();
+        let bounds: Vec<_> = candidates
+            .iter()
+            .map(|(ticker, _)| self.bounds(Some(&ticker.to_string())))
+            .collect();
+        Ok(Picks {
+            ratios: self.cap(&self.allocate(&scores), &bounds)?,
+            max_ratios: bounds.iter().map(|(_, max)| *max).collect(),
+            tickers: candidates.into_iter().map(|(ticker, _)| ticker).collect(),
+        })
+    }
+
+    /// Trades the holdings and the cash toward the ratios of the tickers in the investment
+    /// window, selling whatever exceeds its ratio unless buying only.
+    ///
+    /// Ratios are of the whole portfolio, i.e. the value of the holdings plus the cash, so that
+    /// holdings outside the window are sold in full. When buying only, only the cash is spent.
+    pub fn rebalance(
+        &self,
+        candidates: &ScoringCandidates,
+        scores: &HashMap<Ticker, Score>,
+        skip_count: usize,
+        invest_num: usize,
+        portfolio: &Portfolio,
+        budget: &Budget,
+    ) -> anyhow::Result<Rebalance> {
+        let picks = self.pick(candidates, scores, skip_count, invest_num)?;
+        let mut held: HashMap<Ticker, &Holding> = HashMap::default();
+        for holding in portfolio.holdings.iter() {
+            if !(holding.quantity.is_finite() && holding.quantity >= 0.0) {
+                anyhow::bail!(
+                    "Holding quantity {} of {} is not a non-negative number",
+                    holding.quantity,
+                    holding.ticker
+                )
+            }
+            if held
+                .insert(holding.ticker.as_str().into(), holding)
+                .is_some()
+            {
+                anyhow::bail!("Ticker {} is held more than once", holding.ticker)
+            }
+        }
+
+        let mut tickers = picks.tickers.clone();
+        tickers.extend(
+            portfolio
+                .holdings
+                .iter()
+                .map(|holding| Ticker::from(holding.ticker.as_str()))
+                .filter(|ticker| !picks.tickers.contains(ticker)),
+        );
+        let prices = tickers
+            .iter()
+            .map(|ticker| {
+                budget
+                    .prices
+                    .get(ticker)
+                    .copied()
+                    .filter(|price| price.is_finite() && *price > 0.0)
+                    .ok_or_else(|| anyhow::anyhow!("No price of {} to rebalance with", ticker))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let quantities: Vec<_> = tickers
+            .iter()
+            .map(|ticker| held.get(ticker).map_or(0.0, |holding| holding.quantity))
+            .collect();
+        let values: Vec<_> = quantities.iter().zip(&prices).map(|(q, p)| q * p).collect();
+        let total_value = values.iter().sum::<f64>() + budget.cash;
+        if total_value <= 0.0 {
+            anyhow::bail!("Nothing to rebalance without any holding or cash")
+        }
+        // Tickers outside the window are targeted at 0.
+        let target_ratios: Vec<_> = (0..tickers.len())
+            .map(|i| picks.ratios.get(i).copied().unwrap_or_default())
+            .collect();
+
+        let step = if budget.fractional_shares {
+            FRACTIONAL_SHARE_STEP
+        } else {
+            1.0
+        };
+        let sold: Vec<_> = (0..tickers.len())
+            .map(|i| {
+                if portfolio.buy_only {
+                    0.0
+                } else if target_ratios[i] == 0.0 {
+                    quantities[i]
+                } else {
+                    let excess = values[i] - target_ratios[i] * total_value;
+                    let lots = (excess / (prices[i] * step)).round();
+                    (lots * step).clamp(0.0, quantities[i])
+                }
+            })
+            .collect();
+        let kept_values: Vec<_> = (0..tickers.len())
+            .map(|i| values[i] - sold[i] * prices[i])
+            .collect();
+        let cash = budget.cash + sold.iter().zip(&prices).map(|(q, p)| q * p).sum::<f64>();
+
+        // Holdings above their ratios by less than a share are not sold, so the cash can't meet
+        // every other ratio and is split as when buying only. Sold tickers aren't bought back.
+        let buy_targets: Vec<_> = (0..tickers.len())
+            .map(|i| if sold[i] > 0.0 { 0.0 } else { target_ratios[i] })
+            .collect();
+        let goals = self.fill(&kept_values, &buy_targets, cash);
+        let (buy_ratios, max_buy_ratios): (Vec<_>, Vec<_>) = (0..tickers.len())
+            .map(|i| {
+                if cash <= 0.0 || buy_targets[i] == 0.0 {
+                    return (0.0, 0.0);
+                }
+                let max_value = picks.max_ratios.get(i).copied().unwrap_or_default() * total_value;
+                (
+                    goals[i] / cash,
+                    ((max_value - kept_values[i]) / cash).max(0.0),
+                )
+            })
+            .unzip();
+        let purchases = self.purchase(
+            &tickers,
+            &buy_ratios,
+            &max_buy_ratios,
+            &Budget {
+                cash,
+                prices: budget.prices.clone(),
+                fractional_shares: budget.fractional_shares,
+            },
+        );
+        let spent: f64 = purchases.iter().map(|purchase| purchase.amount).sum();
+        // Floating point errors grow with the amounts, so the slack is relative to the cash.
+        if spent > cash * (1.0 + TOLERANCE) + TOLERANCE {
+            anyhow::bail!("Spent {} out of {} to rebalance", spent, cash)
+        }
+
+        let trades = (0..tickers.len())
+            .map(|i| {
+                let bought = purchases[i].quantity.unwrap_or_default();
+                let (action, quantity) = if sold[i] > 0.0 {
+                    (TradeAction::Sell, sold[i])
+                } else if bought > 0.0 {
+                    (TradeAction::Buy, bought)
+                } else {
+                    (TradeAction::Hold, 0.0)
+                };
+                let realized_gain = held
+                    .get(&tickers[i])
+                    .and_then(|holding| holding.cost_basis)
+                    .filter(|_| sold[i] > 0.0)
+                    .map(|cost_basis| sold[i] * prices[i] - cost_basis * sold[i] / quantities[i]);
+                Trade {
+                    ticker: tickers[i].to_string(),
+                    action,
+                    quantity: self.arithmetic_renderer.render_quantity(quantity),
+                    amount: self.arithmetic_renderer.render_float(quantity * prices[i]),
+                    current_ratio: self
+                        .arithmetic_renderer
+                        .render_percentage(values[i] / total_value),
+                    target_ratio: self.arithmetic_renderer.render_percentage(target_ratios[i]),
+                    effective_ratio: self
+                        .arithmetic_renderer
+                        .render_percentage((kept_values[i] + bought * prices[i]) / total_value),
+                    realized_gain: realized_gain
+                        .map(|gain| self.arithmetic_renderer.render_float(gain)),
+                }
+            })
+            .collect();
+        Ok(Rebalance {
+            trades,
+            // Only rounds off floating point errors, as asserted above.
+            leftover_cash: self
+                .arithmetic_renderer
+                .render_float((cash - spent).max(0.0)),
+        })
+    }
+
+    /// Splits the cash among the values below their target ratios without selling any more.
+    ///
+    /// The buying tickers are raised to the same fraction of their ratios, which is the greatest
+    /// the cash affords. Tickers already above that fraction are repeatedly left out, since they
+    /// can only be lowered by selling.
+    fn fill(&self, values: &[f64], target_ratios: &[f64], cash: f64) -> Vec<f64> {
+        let mut buying: Vec<_> = target_ratios.iter().map(|ratio| *ratio > 0.0).collect();
+        loop {
+            let indexes: Vec<_> = (0..values.len()).filter(|&i| buying[i]).collect();
+            let total_ratio: f64 = indexes.iter().map(|&i| target_ratios[i]).sum();
+            if total_ratio <= 0.0 {
+                return vec![0.0; values.len()];
+            }
+            // Portfolio value at which the buying tickers would exactly meet their ratios.
+            let level = (cash + indexes.iter().map(|&i| values[i]).sum::<f64>()) / total_ratio;
+            let overweight: Vec<_> = indexes
+                .iter()
+                .copied()
+                .filter(|&i| target_ratios[i] * level < values[i] - TOLERANCE)
+                .collect();
+            if overweight.is_empty() {
+                return (0..values.len())
+                    .map(|i| {
+                        if buying[i] {
+                            target_ratios[i] * level - values[i]
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect();
+            }
+            for i in overweight {
+                buying[i] = false;
+            }
+        }
+    }
+
+    /// Buys shares for the ratios of cash, spending the whole amount on tickers without a price.
+    ///
+    /// Each quantity is rounded either down or up, picking the combination that the cash affords
+    /// with the least total deviation of the amounts from their targets, without rounding any
+    /// amount above its maximum ratio. Ties go to rounding up the tickers ranked higher.
+    fn purchase(
+        &self,
+        tickers: &[Ticker],
+        ratios: &[f64],
+        max_ratios: &[f64],
+        budget: &Budget,
+    ) -> Vec<Purchase> {
+        let step = if budget.fractional_shares {
+            FRACTIONAL_SHARE_STEP
+        } else {
+            1.0
+        };
+        let targets: Vec<_> = ratios.iter().map(|ratio| budget.cash * ratio).collect();
+        // Cost of the smallest quantity that can be bought.
+        let lot_prices: Vec<_> = tickers
+            .iter()
+            .map(|ticker| {
+                budget
+                    .prices
+                    .get(ticker)
+                    .filter(|price| price.is_finite() && **price > 0.0)
+                    .map(|price| price * step)
+            })
+            .collect();
+        let mut lots: Vec<_> = targets
+            .iter()
+            .zip(&lot_prices)
+            .map(|(target, lot_price)| {
+                lot_price.map_or(0.0, |lot_price| (target / lot_price + TOLERANCE).floor())
+            })
+            .collect();
+        let amount_of = |lots: &[f64], i: usize| lot_prices[i].map_or(targets[i], |p| lots[i] * p);
+
+        let leftover_cash = budget.cash - (0..lots.len()).map(|i| amount_of(&lots, i)).sum::<f64>();
+        let round_ups: Vec<_> = (0..lots.len())
+            .filter_map(|i| {
+                let lot_price = lot_prices[i]?;
+                let amount = amount_of(&lots, i);
+                if amount + lot_price > budget.cash * max_ratios[i] + TOLERANCE {
+                    return None;
+                }
+                let shortfall = targets[i] - amount;
+                let improvement = shortfall.abs() - (shortfall - lot_price).abs();
+                (improvement > TOLERANCE).then_some(RoundUp {
+                    index: i,
+                    cost: lot_price,
+                    improvement,
+                })
+            })
+            .collect();
+        for i in self.round_up(&round_ups, leftover_cash) {
+            lots[i] += 1.0;
+        }
+
+        (0..lots.len())
+            .map(|i| {
+                let amount = amount_of(&lots, i);
+                Purchase {
+                    amount,
+                    quantity: lot_prices[i].map(|_| lots[i] * step),
+                    effective_ratio: if budget.cash > 0.0 {
+                        amount / budget.cash
+                    } else {
+                        ratios[i]
+                    },
+                }
+            })
             .collect()
     }
 
-    fn build_entry(&self, ticker: &Ticker, score: &Score, total_score: f64) -> StockAdvice {
-        let ratio = score.value / total_score;
+    /// Picks the quantities to round up that reduce the deviation the most within the cash.
+    ///
+    /// This is a knapsack problem, searched by branch and bound in the order the tickers are
+    /// ranked, pruning the branches which can't do better even if the rest could be rounded up
+    /// in fractions.
+    fn round_up(&self, round_ups: &[RoundUp], cash: f64) -> Vec<usize> {
+        let mut by_density: Vec<_> = (0..round_ups.len()).collect();
+        by_density.sort_by(|&a, &b| round_ups[b].density().total_cmp(&round_ups[a].density()));
+        let mut search = RoundUpSearch {
+            round_ups,
+            by_density,
+            chosen: Vec::default(),
+            best: Vec::default(),
+            best_improvement: 0.0,
+        };
+        search.visit(0, cash, 0.0);
+        search.best
+    }
+
+    /// Least and greatest ratios of a ticker, or the global ones without a ticker.
+    fn bounds(&self, ticker: Option<&str>) -> (f64, f64) {
+        let overrides = ticker
+            .and_then(|ticker| self.caps.tickers.get(ticker))
+            .copied()
+            .unwrap_or_default();
+        (
+            overrides.min_ratio.or(self.caps.min_ratio).unwrap_or(0.0),
+            overrides.max_ratio.or(self.caps.max_ratio).unwrap_or(1.0),
+        )
+    }
+
+    /// Clamps the ratios within their bounds, redistributing the difference proportionally among
+    /// the others until all of them fit.
+    ///
+    /// Each round pins either the ratios above their maximum or those below their minimum,
+    /// whichever side misses by more, since redistributing that side only pushes the ratios
+    /// further in the same direction.
+    fn cap(&self, ratios: &[f64], bounds: &[(f64, f64)]) -> anyhow::Result<Vec<f64>> {
+        let total_min: f64 = bounds.iter().map(|(min, _)| min).sum();
+        let total_max: f64 = bounds.iter().map(|(_, max)| max).sum();
+        if total_min > 1.0 + TOLERANCE || total_max < 1.0 - TOLERANCE {
+            anyhow::bail!(
+                "Caps of the {} tickers to invest in add up to {} to {}, which excludes 100%; {}",
+                ratios.len(),
+                self.arithmetic_renderer.render_percentage(total_min),
+                self.arithmetic_renderer.render_percentage(total_max),
+                if total_min > 1.0 + TOLERANCE {
+                    "invest in fewer tickers or lower min_ratio"
+                } else {
+                    "invest in more tickers or raise max_ratio"
+                }
+            )
+        }
+
+        let mut pinned: Vec<Option<f64>> = vec![None; ratios.len()];
+        loop {
+            let free: Vec<_> = (0..ratios.len()).filter(|&i| pinned[i].is_none()).collect();
+            let remaining = 1.0 - pinned.iter().flatten().sum::<f64>();
+            let free_total: f64 = free.iter().map(|&i| ratios[i]).sum();
+            let mut capped: Vec<_> = pinned.iter().map(|ratio| ratio.unwrap_or(0.0)).collect();
+            for &i in free.iter() {
+                capped[i] = if free_total > 0.0 {
+                    remaining * ratios[i] / free_total
+                } else {
+                    remaining / free.len() as f64
+                };
+            }
+            let excess: f64 = free
+                .iter()
+                .map(|&i| (capped[i] - bounds[i].1).max(0.0))
+                .sum();
+            let shortfall: f64 = free
+                .iter()
+                .map(|&i| (bounds[i].0 - capped[i]).max(0.0))
+                .sum();
+            if excess <= TOLERANCE && shortfall <= TOLERANCE {
+                return Ok(capped);
+            }
+            for &i in free.iter() {
+                let (min, max) = bounds[i];
+                if excess >= shortfall && capped[i] > max {
+                    pinned[i] = Some(max);
+                } else if shortfall >= excess && capped[i] < min {
+                    pinned[i] = Some(min);
+                }
+            }
+        }
+    }
+
+    /// Splits the cash among the scores, evenly if none of them is positive.
+    fn allocate(&self, scores: &[f64]) -> Vec<f64> {
+        let total_score: f64 = scores.iter().map(|score| score.max(0.0)).sum();
+        let weights: Vec<_> = match self.allocation {
+            Allocation::Proportional => scores.iter().map(|score| score.max(0.0)).collect(),
+            Allocation::Equal => vec![1.0; scores.len()],
+            Allocation::Softmax { temperature } => {
+                let ratios: Vec<_> = scores
+                    .iter()
+                    .map(|score| score.max(0.0) / total_score)
+                    .collect();
+                // Subtracting the greatest ratio keeps the exponentials from overflowing.
+                let max_ratio = ratios.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                ratios
+                    .iter()
+                    .map(|ratio| ((ratio - max_ratio) / temperature).exp())
+                    .collect()
+            }
+            Allocation::SquareRoot => scores.iter().map(|score| score.max(0.0).sqrt()).collect(),
+        };
+        let total_weight: f64 = weights.iter().sum();
+        if total_weight > 0.0 && total_weight.is_finite() {
+            weights.iter().map(|weight| weight / total_weight).collect()
+        } else {
+            vec![1.0 / scores.len() as f64; scores.len()]
+        }
+    }
+
+    fn build_entry(&self, ticker: &Ticker, ratio: f64, purchase: Option<Purchase>) -> StockAdvice {
         let ratio_text = self.arithmetic_renderer.render_percentage(ratio);
         StockAdvice {
             ticker: ticker.to_string(),
             ratio: ratio_text,
+            effective_ratio: purchase.as_ref().map(|purchase| {
+                self.arithmetic_renderer
+                    .render_percentage(purchase.effective_ratio)
+            }),
+            amount: purchase
+                .as_ref()
+                .map(|purchase| self.arithmetic_renderer.render_float(purchase.amount)),
+            quantity: purchase
+                .and_then(|purchase| purchase.quantity)
+                .map(|quantity| self.arithmetic_renderer.render_quantity(quantity)),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::case;
+
+    fn allocate(allocation: Allocation, scores: &[f64]) -> Vec<String> {
+        let advisor =
+            InvestAdvisor::new(Default::default(), allocation, Default::default()).unwrap();
+        advisor
+            .allocate(scores)
+            .into_iter()
+            .map(|ratio| ArithmeticRenderer.render_percentage(ratio))
+            .collect()
+    }
+
+    #[case(Allocation::Proportional => vec!["64%", "36%"] ; "Proportional")]
+    #[case(Allocation::Equal => vec!["50%", "50%"] ; "Equal")]
+    #[case(Allocation::SquareRoot => vec!["57.14%", "42.86%"] ; "Square root")]
+    #[case(Allocation::Softmax { temperature: 0.1 } => vec!["94.27%", "5.73%"] ; "Cold softmax")]
+    #[case(Allocation::Softmax { temperature: 10.0 } => vec!["50.7%", "49.3%"] ; "Hot softmax")]
+    fn allocate_curves(allocation: Allocation) -> Vec<String> {
+        allocate(allocation, &[16.0, 9.0])
+    }
+
+    #[test]
+    fn allocate_without_positive_score() {
+        assert_eq!(
+            vec!["50%", "50%"],
+            allocate(Allocation::Proportional, &[0.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn new_with_non_positive_temperature() {
+        assert!(
+            InvestAdvisor::new(
+                Default::default(),
+                Allocation::Softmax { temperature: 0.0 },
+                Default::default()
+            )
+            .is_err()
+        );
+    }
+
+    fn cap(ratios: &[f64], bounds: &[(f64, f64)]) -> anyhow::Result<Vec<String>> {
+        Ok(InvestAdvisor::default()
+            .cap(ratios, bounds)?
+            .into_iter()
+            .map(|ratio| ArithmeticRenderer.render_percentage(ratio))
+            .collect())
+    }
+
+    #[case(&[0.5, 0.3, 0.2], &[(0.0, 1.0); 3] => vec!["50%", "30%", "20%"] ; "Within bounds")]
+    #[case(&[0.7, 0.296, 0.004], &[(0.0, 0.4); 3] => vec!["40%", "40%", "20%"] ; "Ceiling cascades")]
+    #[case(&[0.7, 0.296, 0.004], &[(0.05, 1.0); 3] => vec!["66.77%", "28.23%", "5%"] ; "Floor")]
+    #[case(&[0.98, 0.01, 0.01], &[(0.0, 0.5), (0.3, 1.0), (0.3, 1.0)] => vec!["40%", "30%", "30%"] ; "Floor outweighs ceiling")]
+    #[case(&[0.0, 0.0], &[(0.0, 0.6); 2] => vec!["50%", "50%"] ; "Zero ratios")]
+    fn cap_ratios(ratios: &[f64], bounds: &[(f64, f64)]) -> Vec<String> {
+        cap(ratios, bounds).unwrap()
+    }
+
+    #[case(&[(0.6, 1.0); 2] => "Caps of the 2 tickers to invest in add up to 120% to 200%, which excludes 100%; invest in fewer tickers or lower min_ratio" ; "Floors over 100%")]
+    #[case(&[(0.0, 0.4); 2] => "Caps of the 2 tickers to invest in add up to 0% to 80%, which excludes 100%; invest in more tickers or raise max_ratio" ; "Ceilings under 100%")]
+    fn cap_infeasible(bounds: &[(f64, f64)]) -> String {
+        cap(&[0.5, 0.5], bounds).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn bounds_with_override() {
+        // Given
+        let caps = Caps {
+            min_ratio: Some(0.05),
+            max_ratio: Some(0.4),
+            tickers: [(
+                "A".into(),
+                crate::config::TickerCaps {
+                    min_ratio: None,
+                    max_ratio: Some(0.2),
+                },
+            )]
+            .into(),
+        };
+
+        // When
+        let advisor = InvestAdvisor::new(Default::default(), Default::default(), caps).unwrap();
+
+        // Then
+        assert_eq!((0.05, 0.2), advisor.bounds(Some("A")));
+        assert_eq!((0.05, 0.4), advisor.bounds(Some("B")));
+    }
+
+    fn budget(fractional_shares: bool) -> Budget {
+        Budget {
+            cash: 1000.0,
+            prices: [("A".into(), 150.0), ("B".into(), 0.0)].into(),
+            fractional_shares,
+        }
+    }
+
+    #[test]
+    fn render_advice_with_budget() {
+        // Given
+        let candidates: ScoringCandidates = [
+            ("A", HashMap::default()),
+            ("B", HashMap::default()),
+            ("C", HashMap::default()),
+        ]
+        .into();
+        let scores = [
+            ("A".into(), 0.6.into()),
+            ("B".into(), 0.3.into()),
+            ("C".into(), 0.1.into()),
+        ]
+        .into();
+
+        // When
+        let advice = InvestAdvisor::default()
+            .render_advice(&candidates, &scores, 0, 2, Some(&budget(false)))
+            .unwrap();
+
+        // Then
+        let entries: Vec<_> = advice
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.ticker.as_str(),
+                    entry.effective_ratio.as_deref(),
+                    entry.amount.as_deref(),
+                    entry.quantity.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("A", Some("60%"), Some("600"), Some("4")),
+                ("B", Some("33.33%"), Some("333.33"), None)
+            ],
+            entries
+        );
+        assert_eq!(Some("66.67".to_string()), advice.leftover_cash);
+    }
+
+    #[case(&[250.0, 100.0], &[0.5, 0.5], &[1.0, 1.0], false => vec!["2", "5"] ; "Exact amounts")]
+    #[case(&[300.0, 180.0], &[0.5, 0.5], &[1.0, 1.0], false => vec!["2", "2"] ; "Rounding up with leftover cash")]
+    #[case(&[300.0, 120.0], &[0.5, 0.5], &[1.0, 1.0], false => vec!["1", "4"] ; "Rounding up unaffordable")]
+    #[case(&[300.0, 300.0], &[0.55, 0.45], &[1.0, 1.0], false => vec!["2", "1"] ; "Rounding up the furthest below target")]
+    #[case(&[300.0, 180.0], &[0.5, 0.5], &[0.5, 0.5], false => vec!["1", "2"] ; "Rounding up above maximum ratio")]
+    #[case(&[150.0, 70.0], &[0.5, 0.5], &[1.0, 1.0], true => vec!["3.3333", "7.1429"] ; "Fractional shares")]
+    #[case(&[700.0, 380.0, 380.0], &[0.48, 0.26, 0.26], &[1.0; 3], false => vec!["0", "1", "1"] ; "Rounding up two small lots over a big one")]
+    fn purchase(
+        prices: &[f64],
+        ratios: &[f64],
+        max_ratios: &[f64],
+        fractional_shares: bool,
+    ) -> Vec<String> {
+        let tickers: Vec<Ticker> = ["A", "B", "C"][..prices.len()]
+            .iter()
+            .map(|&ticker| ticker.into())
+            .collect();
+        let budget = Budget {
+            cash: 1000.0,
+            prices: tickers
+                .iter()
+                .cloned()
+                .zip(prices.iter().copied())
+                .collect(),
+            fractional_shares,
+        };
+        InvestAdvisor::default()
+            .purchase(&tickers, ratios, max_ratios, &budget)
+            .into_iter()
+            .map(|purchase| ArithmeticRenderer.render_quantity(purchase.quantity.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn purchase_with_least_deviation() {
+        // Given
+        let tickers: Vec<Ticker> = vec!["A".into(), "B".into(), "C".into()];
+        let budget = Budget {
+            cash: 1000.0,
+            prices: [
+                ("A".into(), 700.0),
+                ("B".into(), 380.0),
+                ("C".into(), 380.0),
+            ]
+            .into(),
+            fractional_shares: false,
+        };
+        let ratios = [0.48, 0.26, 0.26];
+
+        // When
+        let purchases = InvestAdvisor::default().purchase(&tickers, &ratios, &[1.0; 3], &budget);
+
+        // Then
+        // Rounding up A first would leave too little cash for B or C, deviating by 740.
+        let deviation: f64 = purchases
+            .iter()
+            .zip(ratios)
+            .map(|(purchase, ratio)| (budget.cash * ratio - purchase.amount).abs())
+            .sum();
+        assert_eq!("720", ArithmeticRenderer.render_float(deviation));
+    }
+
+    fn rebalance(buy_only: bool, prices: &[(&str, f64)]) -> anyhow::Result<Rebalance> {
+        let candidates: ScoringCandidates =
+            [("A", HashMap::default()), ("B", HashMap::default())].into();
+        let scores = [("A".into(), 0.75.into()), ("B".into(), 0.25.into())].into();
+        let portfolio = Portfolio {
+            holdings: vec![
+                Holding {
+                    ticker: "B".into(),
+                    quantity: 10.0,
+                    cost_basis: Some(800.0),
+                },
+                Holding {
+                    ticker: "C".into(),
+                    quantity: 5.0,
+                    cost_basis: None,
+                },
+            ],
+            buy_only,
+        };
+        let budget = Budget {
+            cash: 900.0,
+            prices: prices
+                .iter()
+                .map(|(ticker, price)| (Ticker::from(*ticker), *price))
+                .collect(),
+            fractional_shares: false,
+        };
+        InvestAdvisor::default().rebalance(&candidates, &scores, 0, 2, &portfolio, &budget)
+    }
+
+    fn render_trades(rebalance: &Rebalance) -> Vec<String> {
+        rebalance
+            .trades
+            .iter()
+            .map(|trade| {
+                let action = match trade.action {
+                    TradeAction::Buy => "Buy",
+                    TradeAction::Sell => "Sell",
+                    TradeAction::Hold => "Hold",
+                };
+                format!(
+                    "{} {} {} to {} gaining {:?}",
+                    trade.ticker,
+                    action,
+                    trade.quantity,
+                    trade.effective_ratio,
+                    trade.realized_gain
+                )
+            })
+            .collect()
+    }
+
+    #[case(false => vec![
+        "A Buy 30 to 75% gaining None",
+        "B Sell 5 to 25% gaining Some(\"100\")",
+        "C Sell 5 to 0% gaining None",
+    ] ; "Buying and selling")]
+    #[case(true => vec![
+        "A Buy 18 to 45% gaining None",
+        "B Hold 0 to 50% gaining None",
+        "C Hold 0 to 5% gaining None",
+    ] ; "Buying only")]
+    fn rebalance_portfolio(buy_only: bool) -> Vec<String> {
+        // Holdings are worth 1000 of B and 100 of C, plus 900 of cash.
+        let rebalance = rebalance(buy_only, &[("A", 50.0), ("B", 100.0), ("C", 20.0)]).unwrap();
+        assert_eq!("0", rebalance.leftover_cash);
+        render_trades(&rebalance)
+    }
+
+    #[test]
+    fn rebalance_without_price() {
+        let error = rebalance(false, &[("A", 50.0), ("B", 100.0)])
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!("No price of C to rebalance with", error);
+    }
+
+    #[test]
+    fn rebalance_without_selling_small_excess() {
+        // Given
+        let candidates: ScoringCandidates =
+            [("A", HashMap::default()), ("B", HashMap::default())].into();
+        let scores = [("A".into(), 0.75.into()), ("B".into(), 0.25.into())].into();
+        // B is worth 700 against a target of 500, less than half a share above it.
+        let portfolio = Portfolio {
+            holdings: vec![Holding {
+                ticker: "B".into(),
+                quantity: 1.0,
+                cost_basis: None,
+            }],
+            buy_only: false,
+        };
+        let budget = Budget {
+            cash: 1300.0,
+            prices: [("A".into(), 50.0), ("B".into(), 700.0)].into(),
+            fractional_shares: false,
+        };
+
+        // When
+        let rebalance = InvestAdvisor::default()
+            .rebalance(&candidates, &scores, 0, 2, &portfolio, &budget)
+            .unwrap();
+
+        // Then
+        assert_eq!(
+            vec![
+                "A Buy 26 to 65% gaining None",
+                "B Hold 0 to 35% gaining None"
+            ],
+            render_trades(&rebalance)
+        );
+        assert_eq!("0", rebalance.leftover_cash);
+    }
+
+    #[test]
+    fn rebalance_huge_cash_in_fractional_shares() {
+        // Given
+        let candidates: ScoringCandidates = [
+            ("A", HashMap::default()),
+            ("B", HashMap::default()),
+            ("C", HashMap::default()),
+            ("D", HashMap::default()),
+        ]
+        .into();
+        let scores = [
+            ("A".into(), 0.021149182226508856.into()),
+            ("B".into(), 0.16275699902325869.into()),
+            ("C".into(), 0.6983539117500186.into()),
+            ("D".into(), 0.8235820448026061.into()),
+        ]
+        .into();
+        let portfolio = Portfolio {
+            holdings: vec![Holding {
+                ticker: "A".into(),
+                quantity: 18.0,
+                cost_basis: None,
+            }],
+            buy_only: false,
+        };
+        let budget = Budget {
+            cash: 6.69e14,
+            prices: [
+                ("A".into(), 792.47),
+                ("B".into(), 1797.1),
+                ("C".into(), 233.04),
+                ("D".into(), 119.8),
+            ]
+            .into(),
+            fractional_shares: true,
+        };
+
+        // When
+        let rebalance = InvestAdvisor::default()
+            .rebalance(&candidates, &scores, 0, 4, &portfolio, &budget)
+            .unwrap();
+
+        // Then
+        // Amounts this large add up with floating point errors above an absolute tolerance.
+        assert_eq!(4, rebalance.trades.len());
+    }
+
+    #[case(&[0.0, 1000.0], &[0.75, 0.25], 900.0 => vec![900.0, 0.0] ; "Overweight left out")]
+    #[case(&[100.0, 100.0], &[0.5, 0.5], 200.0 => vec![100.0, 100.0] ; "Balanced")]
+    #[case(&[100.0, 0.0], &[0.5, 0.5], 50.0 => vec![0.0, 50.0] ; "Furthest below first")]
+    #[case(&[100.0, 0.0], &[0.0, 0.0], 50.0 => vec![0.0, 0.0] ; "No target")]
+    fn fill(values: &[f64], target_ratios: &[f64], cash: f64) -> Vec<f64> {
+        InvestAdvisor::default().fill(values, target_ratios, cash)
+    }
+
+    #[test]
+    fn new_with_inverted_caps() {
+        let caps = Caps {
+            min_ratio: Some(0.5),
+            max_ratio: Some(0.4),
+            tickers: Default::default(),
+        };
+        assert!(InvestAdvisor::new(Default::default(), Default::default(), caps).is_err());
+    }
+}
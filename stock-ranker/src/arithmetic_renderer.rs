@@ -3,10 +3,16 @@ pub struct ArithmeticRenderer;
 
 impl ArithmeticRenderer {
     pub fn render_float(&self, value: f64) -> String {
-        format!("{:.2}", value)
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .into()
+        self.trim(format!("{:.2}", value))
+    }
+
+    /// Renders share quantities precisely enough for fractional shares.
+    pub fn render_quantity(&self, quantity: f64) -> String {
+        self.trim(format!("{:.4}", quantity))
+    }
+
+    fn trim(&self, text: String) -> String {
+        text.trim_end_matches('0').trim_end_matches('.').into()
     }
 
     pub fn render_percentage(&self, percentage: f64) -> String {
@@ -26,6 +32,12 @@ mod test {
         ArithmeticRenderer.render_float(value)
     }
 
+    #[case(3.0 => "3"           ; "Whole")]
+    #[case(0.12345 => "0.1235"  ; "Fractional with rounding")]
+    fn render_quantity(quantity: f64) -> String {
+        ArithmeticRenderer.render_quantity(quantity)
+    }
+
     #[test]
     fn render_change() {
         assert_eq!("28.45%", ArithmeticRenderer.render_percentage(0.284513));
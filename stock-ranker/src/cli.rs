@@ -0,0 +1,105 @@
+use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use std::path::PathBuf;
+
+/// Ranks stocks by their metrics and advises which ones to invest in.
+#[derive(Parser)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Stock metrics in JSON, read from stdin if omitted.
+    #[arg(long, short, global = true)]
+    pub input: Option<PathBuf>,
+
+    /// Where to write the result, printed to stdout if omitted.
+    #[arg(long, short, global = true)]
+    pub output: Option<PathBuf>,
+
+    /// How many stocks to skip from the top scores [env: STOCK_RANKER_SKIP_COUNT]
+    #[arg(long, global = true)]
+    pub skip_count: Option<usize>,
+
+    /// How many stocks to invest in [env: STOCK_RANKER_INVEST_COUNT]
+    #[arg(long, global = true)]
+    pub invest_count: Option<usize>,
+
+    /// How to present the result [env: STOCK_RANKER_OUTPUT_FORMAT]
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Configuration of the ranking algorithm, defaults to `stock-ranker.toml` if it exists [env: STOCK_RANKER_CONFIG]
+    #[arg(long, short, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Draw borders around the tables [env: STOCK_RANKER_TABLE_BORDER]
+    #[arg(long, global = true)]
+    pub border: bool,
+
+    /// Cash to invest this month, to advise amounts and share quantities [env: STOCK_RANKER_CASH]
+    #[arg(long, global = true)]
+    pub cash: Option<f64>,
+
+    /// Allow buying fractions of a share instead of whole shares only [env: STOCK_RANKER_FRACTIONAL_SHARES]
+    #[arg(long, global = true)]
+    pub fractional_shares: bool,
+
+    /// Abort on any issue in the input instead of dropping invalid entries [env: STOCK_RANKER_STRICT]
+    #[arg(long, global = true)]
+    pub strict: bool,
+}
+
+#[derive(Subcommand, Clone, Default)]
+pub enum Command {
+    /// Print both the performance report and the investment advice.
+    #[default]
+    Rank,
+
+    /// Print only the performance report.
+    Report,
+
+    /// Print only the investment advice.
+    Advise,
+
+    /// Explain how a ticker is ranked and why it is invested in or not.
+    Explain { ticker: String },
+
+    /// Check the input for mistakes without ranking it.
+    Validate,
+
+    /// Advise trades that move the whole portfolio toward the ratios of the investment advice.
+    Rebalance {
+        /// Current holdings in JSON, see `Holding.json` in the JSON schema
+        #[arg(long)]
+        holdings: PathBuf,
+
+        /// Only buy with the cash, never sell
+        #[arg(long)]
+        buy_only: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Default, PartialEq, Debug)]
+pub enum OutputFormat {
+    /// Tables rendered by stock-ranker itself
+    #[default]
+    Table,
+
+    /// Tables rendered by PowerShell, which must be installed
+    #[value(name = "powershell")]
+    PowerShell,
+
+    /// The result as a JSON document, see `Output.json`, `Advice.json` and `Rebalance.json` in the JSON schema
+    Json,
+
+    /// Each table as CSV, without the leftover cash which fits no table
+    Csv,
+
+    /// Each table in GitHub-flavored Markdown
+    Markdown,
+
+    /// A self-contained HTML page with tables and charts
+    Html,
+}
@@ -0,0 +1,433 @@
+use crate::scoring_candidate::ScoringFactor;
+use anyhow::Context;
+use derive_more::Display;
+use schema::ProductMetric;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Configuration file looked up in the working directory when none is specified.
+pub const DEFAULT_CONFIG_PATH: &str = "stock-ranker.toml";
+
+/// Content of the configuration file, which tunes the algorithm without recompiling.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Which ranker runs on which factor.
+    pub rankers: Vec<RankerConfig>,
+
+    /// How much each factor contributes to the final score.
+    pub weights: HashMap<ScoringFactor, f64>,
+
+    /// How to order tickers with the same score, before comparing the tickers themselves.
+    #[serde(default)]
+    pub tie_breakers: Vec<TieBreaker>,
+
+    /// How a ticker lacking some factors is scored.
+    #[serde(default)]
+    pub missing_factors: MissingFactorPolicy,
+
+    /// How the cash is split among the tickers to invest in.
+    #[serde(default)]
+    pub allocation: Allocation,
+
+    /// Bounds of the ratio of cash for each ticker to invest in.
+    #[serde(default)]
+    pub caps: Caps,
+
+    /// Which products to rank by their metadata.
+    #[serde(default)]
+    pub filter: Filter,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rankers: vec![
+                RankerConfig {
+                    kind: RankerKind::NegativeLeastWinning,
+                    factor: ScoringFactor::OneMonthPriceChange,
+                },
+                RankerConfig {
+                    kind: RankerKind::PositiveGreatestWinning,
+                    factor: ScoringFactor::LongTermTotalReturn,
+                },
+            ],
+            weights: HashMap::from([
+                (ScoringFactor::OneMonthPriceChange, 6.0),
+                (ScoringFactor::LongTermTotalReturn, 4.0),
+            ]),
+            tie_breakers: Default::default(),
+            missing_factors: Default::default(),
+            allocation: Default::default(),
+            caps: Default::default(),
+            filter: Default::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the configuration {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid configuration {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct RankerConfig {
+    pub kind: RankerKind,
+    pub factor: ScoringFactor,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Display)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+pub enum RankerKind {
+    /// Favors the greatest loss, see `NegativeLeastWinningRanker`.
+    #[display("negative_least_winning")]
+    NegativeLeastWinning,
+
+    /// Favors the greatest gain, see `PositiveGreatestWinningRanker`.
+    #[display("positive_greatest_winning")]
+    PositiveGreatestWinning,
+
+    /// Favors the lowest risk, see `PositiveLeastWinningRanker`.
+    #[display("positive_least_winning")]
+    PositiveLeastWinning,
+
+    /// Favors the greatest value by position rather than magnitude, see `OrdinalRanker`.
+    #[display("ordinal_greatest_winning")]
+    OrdinalGreatestWinning,
+
+    /// Favors the smallest value by position rather than magnitude, see `OrdinalRanker`.
+    #[display("ordinal_least_winning")]
+    OrdinalLeastWinning,
+
+    /// Favors the values furthest above the average, see `ZScoreRanker`.
+    #[display("z_score_greatest_winning")]
+    ZScoreGreatestWinning,
+
+    /// Favors the values furthest below the average, see `ZScoreRanker`.
+    #[display("z_score_least_winning")]
+    ZScoreLeastWinning,
+
+    /// Favors the greatest value relative to the range of all values, see `MinMaxRanker`.
+    #[display("min_max_greatest_winning")]
+    MinMaxGreatestWinning,
+
+    /// Favors the smallest value relative to the range of all values, see `MinMaxRanker`.
+    #[display("min_max_least_winning")]
+    MinMaxLeastWinning,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(tag = "by", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TieBreaker {
+    /// Higher value first, or lower value first if `ascending`.
+    Factor {
+        factor: ScoringFactor,
+        #[serde(default)]
+        ascending: bool,
+    },
+
+    /// Display name in alphabetical order.
+    Name,
+
+    /// Ticker in alphabetical order.
+    Ticker,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingFactorPolicy {
+    /// A missing factor contributes nothing, as if the ranker filtered out the ticker.
+    #[default]
+    Zero,
+
+    /// Weights of the missing factors are spread over the present ones in proportion.
+    Rescale,
+
+    /// A ticker lacking any factor in use is not ranked at all.
+    Exclude,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Caps {
+    /// Least ratio of cash for any ticker, 0 if absent.
+    pub min_ratio: Option<f64>,
+
+    /// Greatest ratio of cash for any ticker, 1 if absent.
+    pub max_ratio: Option<f64>,
+
+    /// Overrides of the bounds above for specific tickers.
+    #[serde(default)]
+    pub tickers: HashMap<String, TickerCaps>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TickerCaps {
+    pub min_ratio: Option<f64>,
+    pub max_ratio: Option<f64>,
+}
+
+/// Products to rank, all of them unless some values are listed for a field.
+#[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Filter {
+    #[serde(default)]
+    pub exchanges: Vec<String>,
+
+    #[serde(default)]
+    pub currencies: Vec<String>,
+
+    #[serde(default)]
+    pub sectors: Vec<String>,
+
+    #[serde(default)]
+    pub regions: Vec<String>,
+}
+
+impl Filter {
+    /// Whether the product has one of the listed values for every field listing any, which it
+    /// doesn't if the value is unknown.
+    pub fn accepts(&self, metric: &ProductMetric) -> bool {
+        [
+            (&self.exchanges, &metric.exchange),
+            (&self.currencies, &metric.currency),
+            (&self.sectors, &metric.sector),
+            (&self.regions, &metric.region),
+        ]
+        .into_iter()
+        .all(|(values, value)| {
+            values.is_empty() || value.as_ref().is_some_and(|value| values.contains(value))
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(tag = "curve", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Allocation {
+    /// In proportion to the scores.
+    #[default]
+    Proportional,
+
+    /// The same amount to every ticker.
+    Equal,
+
+    /// Exponentially in the proportional ratios, more concentrated with a lower `temperature`.
+    Softmax { temperature: f64 },
+
+    /// In proportion to the square roots of the scores, less concentrated than proportionally.
+    SquareRoot,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        // Given
+        let text = r#"
+            [[rankers]]
+            kind = "negative_least_winning"
+            factor = "one_month_price_change"
+
+            [[rankers]]
+            kind = "positive_greatest_winning"
+            factor = "long_term_total_return"
+
+            [weights]
+            one_month_price_change = 6.0
+            long_term_total_return = 4.0
+        "#;
+
+        // When
+        let config = Config::parse(text).unwrap();
+
+        // Then
+        assert_eq!(Config::default(), config);
+    }
+
+    #[test]
+    fn parse_unknown_factor() {
+        // Given
+        let text = r#"
+            [[rankers]]
+            kind = "negative_least_winning"
+            factor = "one_week_price_change"
+
+            [weights]
+            one_week_price_change = 1.0
+        "#;
+
+        // When
+        let error = format!("{:#}", Config::parse(text).unwrap_err());
+
+        // Then
+        assert!(error.contains("unknown variant `one_week_price_change`"));
+    }
+
+    #[test]
+    fn parse_tie_breakers() {
+        // Given
+        let text = r#"
+            [[rankers]]
+            kind = "positive_greatest_winning"
+            factor = "long_term_total_return"
+
+            [weights]
+            long_term_total_return = 1.0
+
+            [[tie_breakers]]
+            by = "factor"
+            factor = "one_month_price_change"
+            ascending = true
+
+            [[tie_breakers]]
+            by = "name"
+        "#;
+        let expected = vec![
+            TieBreaker::Factor {
+                factor: ScoringFactor::OneMonthPriceChange,
+                ascending: true,
+            },
+            TieBreaker::Name,
+        ];
+
+        // When
+        let config = Config::parse(text).unwrap();
+
+        // Then
+        assert_eq!(expected, config.tie_breakers);
+    }
+
+    #[test]
+    fn parse_missing_factors() {
+        // Given
+        let text = r#"
+            missing_factors = "rescale"
+
+            [[rankers]]
+            kind = "positive_greatest_winning"
+            factor = "long_term_total_return"
+
+            [weights]
+            long_term_total_return = 1.0
+        "#;
+
+        // When
+        let config = Config::parse(text).unwrap();
+
+        // Then
+        assert_eq!(MissingFactorPolicy::Rescale, config.missing_factors);
+    }
+
+    #[test]
+    fn parse_allocation() {
+        // Given
+        let text = r#"
+            [[rankers]]
+            kind = "positive_greatest_winning"
+            factor = "long_term_total_return"
+
+            [weights]
+            long_term_total_return = 1.0
+
+            [allocation]
+            curve = "softmax"
+            temperature = 0.5
+        "#;
+
+        // When
+        let config = Config::parse(text).unwrap();
+
+        // Then
+        assert_eq!(Allocation::Softmax { temperature: 0.5 }, config.allocation);
+    }
+
+    #[test]
+    fn parse_caps() {
+        // Given
+        let text = r#"
+            [[rankers]]
+            kind = "positive_greatest_winning"
+            factor = "long_term_total_return"
+
+            [weights]
+            long_term_total_return = 1.0
+
+            [caps]
+            max_ratio = 0.4
+
+            [caps.tickers.TSLA]
+            min_ratio = 0.1
+            max_ratio = 0.2
+        "#;
+        let expected = Caps {
+            min_ratio: None,
+            max_ratio: Some(0.4),
+            tickers: [(
+                "TSLA".into(),
+                TickerCaps {
+                    min_ratio: Some(0.1),
+                    max_ratio: Some(0.2),
+                },
+            )]
+            .into(),
+        };
+
+        // When
+        let config = Config::parse(text).unwrap();
+
+        // Then
+        assert_eq!(expected, config.caps);
+    }
+
+    #[test]
+    fn filter_by_metadata() {
+        // Given
+        let text = r#"
+            [[rankers]]
+            kind = "positive_greatest_winning"
+            factor = "long_term_total_return"
+
+            [weights]
+            long_term_total_return = 1.0
+
+            [filter]
+            currencies = ["USD"]
+            sectors = ["Technology"]
+            regions = ["United States", "日本"]
+        "#;
+        let metrics: Vec<ProductMetric> = serde_json::from_str(
+            r#"[
+                { "ticker": "A", "name": "A", "currency": "USD", "sector": "Technology", "region": "United States" },
+                { "ticker": "B", "name": "B", "currency": "JPY", "sector": "Technology", "region": "日本" },
+                { "ticker": "C", "name": "C", "currency": "USD", "sector": "Technology" },
+                { "ticker": "D", "name": "D", "currency": "USD", "sector": "Energy", "region": "United States" },
+                { "ticker": "E", "name": "E", "currency": "USD", "region": "United States" }
+            ]"#,
+        )
+        .unwrap();
+
+        // When
+        let config = Config::parse(text).unwrap();
+
+        // Then
+        let accepted: Vec<_> = metrics
+            .iter()
+            .filter(|metric| config.filter.accepts(metric))
+            .map(|metric| metric.ticker.as_str())
+            .collect();
+        assert_eq!(vec!["A"], accepted);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::arithmetic_renderer::ArithmeticRenderer;
+use crate::config::RankerKind;
+use crate::ranker::FactorContribution;
+use crate::ranker::Score;
+use crate::ranker::ScoreBreakdown;
+use crate::ranker::Ticker;
+use schema::StockAdvice;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Justifies in plain text why a ticker gets its score and whether it is invested in.
+#[derive(Default)]
+pub struct Explainer {
+    arithmetic_renderer: ArithmeticRenderer,
+}
+
+impl Explainer {
+    pub fn explain(
+        &self,
+        ticker: &Ticker,
+        breakdowns: &HashMap<Ticker, ScoreBreakdown>,
+        sorted_scores: &[(Ticker, Score)],
+        advice: Result<&[StockAdvice], &anyhow::Error>,
+        skip_count: usize,
+        invest_count: usize,
+    ) -> anyhow::Result<String> {
+        let Some(breakdown) = breakdowns.get(ticker) else {
+            anyhow::bail!(
+                "Ticker {} is not in the input or is excluded for missing factors",
+                ticker
+            )
+        };
+        let mut text = String::default();
+        writeln!(text, "How {} is ranked:", ticker)?;
+        for contribution in breakdown.contributions.iter() {
+            writeln!(text, "{}", self.explain_contribution(contribution))?;
+        }
+
+        let score = breakdown.total().value;
+        let position = sorted_scores
+            .iter()
+            .position(|(other, _)| other == ticker)
+            .unwrap_or(sorted_scores.len());
+        let tie_count = sorted_scores
+            .iter()
+            .filter(|(other, other_score)| other != ticker && other_score.value == score)
+            .count();
+        writeln!(
+            text,
+            "Total score is {}, ranking #{} of {} tickers{}.",
+            self.render_score(score),
+            position + 1,
+            sorted_scores.len(),
+            if tie_count > 0 {
+                format!(" (tied with {} others, see tie breakers)", tie_count)
+            } else {
+                String::default()
+            }
+        )?;
+
+        writeln!(
+            text,
+            "Advice skips the top {} tickers and invests in the next {}, i.e. #{} to #{}.",
+            skip_count,
+            invest_count,
+            skip_count + 1,
+            skip_count + invest_count
+        )?;
+        let advice = match advice {
+            Ok(advice) => advice,
+            Err(error) => {
+                writeln!(text, "No advice can be given: {}", error)?;
+                return Ok(text);
+            }
+        };
+        let ticker_text = ticker.to_string();
+        let verdict = match advice.iter().find(|entry| entry.ticker == ticker_text) {
+            Some(entry) => format!(
+                "{} is inside the window and receives {} of the cash{}.",
+                ticker,
+                entry.ratio,
+                match (&entry.amount, &entry.quantity) {
+                    (Some(amount), Some(quantity)) => {
+                        format!(", i.e. {} for {} shares", amount, quantity)
+                    }
+                    (Some(amount), None) => format!(", i.e. {}", amount),
+                    _ => String::default(),
+                }
+            ),
+            None if position < skip_count => format!(
+                "{} is not invested in because it is among the top {} tickers to skip.",
+                ticker, skip_count
+            ),
+            None => format!(
+                "{} is not invested in because it ranks below the window.",
+                ticker
+            ),
+        };
+        writeln!(text, "{}", verdict)?;
+        Ok(text)
+    }
+
+    fn explain_contribution(&self, contribution: &FactorContribution) -> String {
+        let value = contribution.notional.map_or_else(
+            || "None".to_string(),
+            |v| {
+                if contribution.factor.is_percentage() {
+                    self.arithmetic_renderer.render_percentage(v.value)
+                } else {
+                    self.arithmetic_renderer.render_float(v.value)
+                }
+            },
+        );
+        let header = format!(
+            "- {} is {}, ranked by {} which {}",
+            contribution.factor,
+            value,
+            contribution.ranker,
+            self.describe_ranker(contribution.ranker)
+        );
+        match contribution.score {
+            Some(score) => format!(
+                "{}:\n  {}, weighted by {} into {} points",
+                header,
+                self.describe_score(contribution.ranker, score.value),
+                self.arithmetic_renderer.render_float(contribution.weight),
+                self.render_score(contribution.weighted_score().value)
+            ),
+            None if contribution.notional.is_none() => {
+                format!("{}:\n  no value to rank, contributing 0 points", header)
+            }
+            None => format!("{}:\n  filtered out, contributing 0 points", header),
+        }
+    }
+
+    fn describe_ranker(&self, ranker: RankerKind) -> &'static str {
+        match ranker {
+            RankerKind::NegativeLeastWinning => {
+                "only considers negative values and favors the greatest loss"
+            }
+            RankerKind::PositiveGreatestWinning => {
+                "only considers positive values and favors the greatest gain"
+            }
+            RankerKind::PositiveLeastWinning => {
+                "only considers values of 0 or above and favors the smallest by the inverse of the value plus 1"
+            }
+            RankerKind::OrdinalGreatestWinning => {
+                "considers all values and favors the greatest by position rather than magnitude"
+            }
+            RankerKind::OrdinalLeastWinning => {
+                "considers all values and favors the smallest by position rather than magnitude"
+            }
+            RankerKind::ZScoreGreatestWinning => {
+                "considers all values and favors the ones furthest above the average"
+            }
+            RankerKind::ZScoreLeastWinning => {
+                "considers all values and favors the ones furthest below the average"
+            }
+            RankerKind::MinMaxGreatestWinning => {
+                "considers all values and favors the greatest relative to their range"
+            }
+            RankerKind::MinMaxLeastWinning => {
+                "considers all values and favors the smallest relative to their range"
+            }
+        }
+    }
+
+    /// How the ranker turns the value into the score.
+    fn describe_score(&self, ranker: RankerKind, score: f64) -> String {
+        let percentage = self.arithmetic_renderer.render_percentage(score);
+        match ranker {
+            RankerKind::NegativeLeastWinning | RankerKind::PositiveGreatestWinning => {
+                format!("normalized to {} of the factor's total", percentage)
+            }
+            RankerKind::PositiveLeastWinning => format!(
+                "inverse of the value plus 1 normalized to {} of the factor's total",
+                percentage
+            ),
+            RankerKind::OrdinalGreatestWinning | RankerKind::OrdinalLeastWinning => {
+                format!("position worth {} of all points", percentage)
+            }
+            RankerKind::ZScoreGreatestWinning | RankerKind::ZScoreLeastWinning => format!(
+                "standard deviations beyond the average normalized to {} of the factor's total",
+                percentage
+            ),
+            RankerKind::MinMaxGreatestWinning | RankerKind::MinMaxLeastWinning => format!(
+                "position in the range from the worst normalized to {} of the factor's total",
+                percentage
+            ),
+        }
+    }
+
+    fn render_score(&self, score: f64) -> String {
+        self.arithmetic_renderer.render_float(score * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scoring_candidate::ScoringFactor;
+    use test_case::case;
+
+    fn breakdown(notional: f64, score: Option<f64>) -> ScoreBreakdown {
+        ScoreBreakdown {
+            contributions: vec![FactorContribution {
+                factor: ScoringFactor::OneMonthPriceChange,
+                ranker: RankerKind::NegativeLeastWinning,
+                notional: Some(notional.into()),
+                score: score.map(Into::into),
+                weight: 2.0,
+            }],
+        }
+    }
+
+    fn sorted_scores() -> Vec<(Ticker, Score)> {
+        vec![
+            ("A".into(), 1.5.into()),
+            ("B".into(), 0.5.into()),
+            ("C".into(), 0.0.into()),
+        ]
+    }
+
+    fn breakdowns() -> HashMap<Ticker, ScoreBreakdown> {
+        [
+            ("A".into(), breakdown(-0.3, Some(0.75))),
+            ("B".into(), breakdown(-0.1, Some(0.25))),
+            ("C".into(), breakdown(0.1, None)),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn explain_invested() {
+        // Given
+        let advice = vec![StockAdvice {
+            ticker: "A".into(),
+            ratio: "100%".into(),
+            effective_ratio: None,
+            amount: None,
+            quantity: None,
+        }];
+        let expected = "\
+How A is ranked:
+- one_month_price_change is -30%, ranked by negative_least_winning which only considers negative values and favors the greatest loss:
+  normalized to 75% of the factor's total, weighted by 2 into 150 points
+Total score is 150, ranking #1 of 3 tickers.
+Advice skips the top 0 tickers and invests in the next 1, i.e. #1 to #1.
+A is inside the window and receives 100% of the cash.
+";
+
+        // When
+        let actual = Explainer::default()
+            .explain(
+                &"A".into(),
+                &breakdowns(),
+                &sorted_scores(),
+                Ok(&advice),
+                0,
+                1,
+            )
+            .unwrap();
+
+        // Then
+        assert_eq!(expected, actual);
+    }
+
+    #[case(RankerKind::PositiveLeastWinning, 0.25 => "inverse of the value plus 1 normalized to 25% of the factor's total" ; "Positive least winning")]
+    #[case(RankerKind::OrdinalLeastWinning, 0.5 => "position worth 50% of all points" ; "Ordinal")]
+    #[case(RankerKind::ZScoreGreatestWinning, 0.75 => "standard deviations beyond the average normalized to 75% of the factor's total" ; "Z-score")]
+    #[case(RankerKind::MinMaxLeastWinning, 0.5714 => "position in the range from the worst normalized to 57.14% of the factor's total" ; "Min-max")]
+    fn describe_score(ranker: RankerKind, score: f64) -> String {
+        Explainer::default().describe_score(ranker, score)
+    }
+
+    #[test]
+    fn explain_filtered_out() {
+        // When
+        let actual = Explainer::default()
+            .explain(&"C".into(), &breakdowns(), &sorted_scores(), Ok(&[]), 0, 2)
+            .unwrap();
+
+        // Then
+        assert!(actual.contains("filtered out, contributing 0 points"));
+        assert!(actual.contains("C is not invested in because it ranks below the window."));
+    }
+
+    #[test]
+    fn explain_skipped() {
+        // When
+        let actual = Explainer::default()
+            .explain(&"A".into(), &breakdowns(), &sorted_scores(), Ok(&[]), 1, 1)
+            .unwrap();
+
+        // Then
+        assert!(actual.contains("among the top 1 tickers to skip"));
+    }
+
+    #[test]
+    fn explain_unknown_ticker() {
+        assert!(
+            Explainer::default()
+                .explain(&"D".into(), &breakdowns(), &sorted_scores(), Ok(&[]), 0, 1)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1 @@
+ConvertFrom-Json | Select-Object ticker, ratio, effective_ratio, amount, quantity | Format-Table
@@ -0,0 +1 @@
+ConvertFrom-Json | Select-Object ticker, action, quantity, amount, current_ratio, target_ratio, effective_ratio, realized_gain | Format-Table
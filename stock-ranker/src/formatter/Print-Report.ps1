@@ -0,0 +1 @@
+ConvertFrom-Json | Select-Object ticker, @{Name="得分"; Expression="score"}, @{Name="單月漲幅"; Expression="one_month_price_change"}, @{Name="三月漲幅"; Expression="three_month_price_change"}, @{Name="六月漲幅"; Expression="six_month_price_change"}, @{Name="十二月漲幅"; Expression="twelve_month_price_change"}, @{Name="一年回報"; Expression="one_year_total_return"}, @{Name="三年回報"; Expression="three_year_total_return"}, @{Name="長期回報"; Expression="long_term_total_return"}, @{Name="波動率"; Expression="annualized_volatility"}, @{Name="最大回撤"; Expression="max_drawdown"}, @{Name="本益比"; Expression="trailing_pe"}, @{Name="預估本益比"; Expression="forward_pe"}, @{Name="殖利率"; Expression="dividend_yield"}, @{Name="EPS成長"; Expression="eps_growth"}, @{Name="總費用率"; Expression="expense_ratio"}, @{Name="資產規模"; Expression="assets_under_management"}, @{Name="追蹤差異"; Expression="tracking_difference"} | Format-Table
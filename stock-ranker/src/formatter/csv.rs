@@ -0,0 +1,71 @@
+use super::OutputFormatter;
+use crate::table_renderer::Table;
+use anyhow::Context;
+use schema::Rebalance;
+use schema::StockAdvice;
+use schema::StockReport;
+
+/// Formats each table as CSV, ready to be imported into spreadsheets.
+pub struct CsvFormatter;
+
+impl OutputFormatter for CsvFormatter {
+    fn format_report(&self, report: &[StockReport]) -> anyhow::Result<String> {
+        self.format_table(&Table::from(report))
+    }
+
+    fn format_advice(
+        &self,
+        advice: &[StockAdvice],
+        _leftover_cash: Option<&str>,
+    ) -> anyhow::Result<String> {
+        // The leftover cash is left out so that the advice stays a single table.
+        self.format_table(&Table::from(advice))
+    }
+
+    fn format_rebalance(&self, rebalance: &Rebalance) -> anyhow::Result<String> {
+        // The leftover cash is left out so that the trades stay a single table.
+        self.format_table(&Table::from(&rebalance.trades[..]))
+    }
+}
+
+impl CsvFormatter {
+    fn format_table(&self, table: &Table) -> anyhow::Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::default());
+        writer
+            .write_record(table.columns().iter().map(|column| column.header()))
+            .context("Failed to write CSV header")?;
+        for row in table.rows() {
+            writer
+                .write_record(row)
+                .context("Failed to write CSV record")?;
+        }
+        let bytes = writer.into_inner().context("Failed to flush CSV writer")?;
+        String::from_utf8(bytes).context("CSV writer produced invalid UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::table_renderer::Alignment;
+    use crate::table_renderer::Column;
+
+    #[test]
+    fn format_table() {
+        // Given
+        let table = Table::new(
+            vec![
+                Column::new("ticker", Alignment::Left),
+                Column::new("name", Alignment::Left),
+            ],
+            vec![vec!["A".into(), "Foo, Inc.".into()]],
+        );
+        let expected = "ticker,name\nA,\"Foo, Inc.\"\n";
+
+        // When
+        let actual = CsvFormatter.format_table(&table).unwrap();
+
+        // Then
+        assert_eq!(expected, actual);
+    }
+}
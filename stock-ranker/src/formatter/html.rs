@@ -0,0 +1,357 @@
+use super::Figures;
+use super::OutputFormatter;
+use crate::arithmetic_renderer::ArithmeticRenderer;
+use crate::ranker::Ticker;
+use crate::table_renderer::Alignment;
+use crate::table_renderer::Table;
+use schema::Output;
+use schema::Rebalance;
+use schema::StockAdvice;
+use schema::StockReport;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+];
+const BAR_HEIGHT: f64 = 24.0;
+const BAR_LABEL_WIDTH: f64 = 80.0;
+const BAR_MAX_WIDTH: f64 = 400.0;
+const PIE_RADIUS: f64 = 100.0;
+
+/// Formats a self-contained HTML page with tables and inline SVG charts, without any external asset.
+#[derive(Default)]
+pub struct HtmlFormatter {
+    arithmetic_renderer: ArithmeticRenderer,
+    figures: Figures,
+}
+
+impl OutputFormatter for HtmlFormatter {
+    fn format_report(&self, report: &[StockReport]) -> anyhow::Result<String> {
+        Ok(self.format_document(&self.format_report_section(report)))
+    }
+
+    fn format_advice(
+        &self,
+        advice: &[StockAdvice],
+        leftover_cash: Option<&str>,
+    ) -> anyhow::Result<String> {
+        Ok(self.format_document(&self.format_advice_section(advice, leftover_cash)))
+    }
+
+    fn format_rebalance(&self, rebalance: &Rebalance) -> anyhow::Result<String> {
+        let body = format!(
+            "<h2>Rebalancing advice</h2>\n{}<p>Leftover cash: {}</p>\n",
+            self.format_table(&Table::from(&rebalance.trades[..])),
+            self.escape(&rebalance.leftover_cash)
+        );
+        Ok(self.format_document(&body))
+    }
+
+    fn format_output(&self, output: &Output) -> anyhow::Result<String> {
+        let body = format!(
+            "{}{}",
+            self.format_report_section(&output.report),
+            self.format_advice_section(&output.advice, output.leftover_cash.as_deref())
+        );
+        Ok(self.format_document(&body))
+    }
+}
+
+impl HtmlFormatter {
+    pub fn new(figures: Figures) -> Self {
+        Self {
+            arithmetic_renderer: Default::default(),
+            figures,
+        }
+    }
+
+    fn format_document(&self, body: &str) -> String {
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Stock ranking</title>
+<style>
+body {{ font-family: sans-serif; }}
+table {{ border-collapse: collapse; margin-bottom: 1em; }}
+th, td {{ border: 1px solid #ccc; padding: 0.2em 0.6em; }}
+.left {{ text-align: left; }}
+.right {{ text-align: right; }}
+</style>
+</head>
+<body>
+{}</body>
+</html>
+"#,
+            body
+        )
+    }
+
+    fn format_report_section(&self, report: &[StockReport]) -> String {
+        // Scores are presented in points as in the report, i.e. in percents.
+        let scores: Vec<_> = report
+            .iter()
+            .map(|entry| {
+                let score = self.figure(&self.figures.scores, &entry.ticker);
+                (entry.ticker.as_str(), score * 100.0)
+            })
+            .collect();
+        format!(
+            "<h2>Stock performance report</h2>\n{}{}",
+            self.format_table(&Table::from(report)),
+            self.format_bar_chart(&scores)
+        )
+    }
+
+    fn format_advice_section(&self, advice: &[StockAdvice], leftover_cash: Option<&str>) -> String {
+        let ratios: Vec<_> = advice
+            .iter()
+            .map(|entry| {
+                let ratio = self.figure(&self.figures.ratios, &entry.ticker);
+                (entry.ticker.as_str(), ratio)
+            })
+            .collect();
+        format!(
+            "<h2>Investment advice for this month</h2>\n{}{}{}",
+            self.format_table(&Table::from(advice)),
+            leftover_cash.map_or_else(String::default, |cash| {
+                format!("<p>Leftover cash: {}</p>\n", self.escape(cash))
+            }),
+            self.format_pie_chart(&ratios)
+        )
+    }
+
+    fn format_table(&self, table: &Table) -> String {
+        let header: String = table
+            .columns()
+            .iter()
+            .map(|column| {
+                format!(
+                    r#"<th class="{}">{}</th>"#,
+                    self.alignment_class(column.alignment()),
+                    self.escape(column.header())
+                )
+            })
+            .collect();
+        let rows: String = table
+            .rows()
+            .iter()
+            .map(|row| {
+                let cells: String = table
+                    .columns()
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| {
+                        format!(
+                            r#"<td class="{}">{}</td>"#,
+                            self.alignment_class(column.alignment()),
+                            self.escape(cell)
+                        )
+                    })
+                    .collect();
+                format!("<tr>{}</tr>\n", cells)
+            })
+            .collect();
+        format!(
+            "<table>\n<thead><tr>{}</tr></thead>\n<tbody>\n{}</tbody>\n</table>\n",
+            header, rows
+        )
+    }
+
+    fn format_bar_chart(&self, values: &[(&str, f64)]) -> String {
+        let max_value = values.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+        let height = BAR_HEIGHT * values.len() as f64;
+        let width = BAR_LABEL_WIDTH + BAR_MAX_WIDTH + BAR_LABEL_WIDTH;
+        let bars: String = values
+            .iter()
+            .enumerate()
+            .map(|(index, (label, value))| {
+                let bar_width = if max_value > 0.0 {
+                    value.max(0.0) / max_value * BAR_MAX_WIDTH
+                } else {
+                    0.0
+                };
+                let y = BAR_HEIGHT * index as f64;
+                let text_y = y + BAR_HEIGHT * 0.7;
+                format!(
+                    concat!(
+                        r#"<text x="0" y="{}">{}</text>"#,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                        r#"<text x="{}" y="{}">{}</text>"#,
+                        "\n"
+                    ),
+                    self.render_coordinate(text_y),
+                    self.escape(label),
+                    self.render_coordinate(BAR_LABEL_WIDTH),
+                    self.render_coordinate(y + 2.0),
+                    self.render_coordinate(bar_width),
+                    self.render_coordinate(BAR_HEIGHT - 4.0),
+                    PALETTE[0],
+                    self.render_coordinate(BAR_LABEL_WIDTH + bar_width + 4.0),
+                    self.render_coordinate(text_y),
+                    self.arithmetic_renderer.render_float(*value)
+                )
+            })
+            .collect();
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n{}</svg>\n",
+            self.render_coordinate(width),
+            self.render_coordinate(height),
+            bars
+        )
+    }
+
+    fn format_pie_chart(&self, ratios: &[(&str, f64)]) -> String {
+        let total: f64 = ratios.iter().map(|(_, ratio)| ratio.max(0.0)).sum();
+        let diameter = PIE_RADIUS * 2.0;
+        let mut start = 0.0;
+        let mut slices = String::default();
+        let mut legend = String::default();
+        for (index, (label, ratio)) in ratios.iter().enumerate() {
+            let color = PALETTE[index % PALETTE.len()];
+            let fraction = if total > 0.0 {
+                ratio.max(0.0) / total
+            } else {
+                0.0
+            };
+            slices.push_str(&self.format_pie_slice(start, fraction, color));
+            start += fraction;
+
+            let legend_y = 20.0 * index as f64;
+            legend.push_str(&format!(
+                concat!(
+                    r#"<rect x="{}" y="{}" width="12" height="12" fill="{}"/>"#,
+                    r#"<text x="{}" y="{}">{} {}</text>"#,
+                    "\n"
+                ),
+                self.render_coordinate(diameter + 20.0),
+                self.render_coordinate(legend_y),
+                color,
+                self.render_coordinate(diameter + 36.0),
+                self.render_coordinate(legend_y + 11.0),
+                self.escape(label),
+                self.arithmetic_renderer.render_percentage(*ratio)
+            ));
+        }
+        let height = diameter.max(20.0 * ratios.len() as f64);
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n{}{}</svg>\n",
+            self.render_coordinate(diameter + 200.0),
+            self.render_coordinate(height),
+            slices,
+            legend
+        )
+    }
+
+    /// Draws a slice starting at `start` and spanning `fraction` of the circle, both in turns.
+    fn format_pie_slice(&self, start: f64, fraction: f64, color: &str) -> String {
+        if fraction <= 0.0 {
+            return String::default();
+        }
+        if fraction >= 1.0 {
+            return format!(
+                "<circle cx=\"{0}\" cy=\"{0}\" r=\"{0}\" fill=\"{1}\"/>\n",
+                self.render_coordinate(PIE_RADIUS),
+                color
+            );
+        }
+        let point = |turn: f64| {
+            let angle = 2.0 * PI * turn - PI / 2.0;
+            (
+                PIE_RADIUS + PIE_RADIUS * angle.cos(),
+                PIE_RADIUS + PIE_RADIUS * angle.sin(),
+            )
+        };
+        let (start_x, start_y) = point(start);
+        let (end_x, end_y) = point(start + fraction);
+        let large_arc = if fraction > 0.5 { 1 } else { 0 };
+        format!(
+            "<path d=\"M {0} {0} L {1} {2} A {0} {0} 0 {3} 1 {4} {5} Z\" fill=\"{6}\"/>\n",
+            self.render_coordinate(PIE_RADIUS),
+            self.render_coordinate(start_x),
+            self.render_coordinate(start_y),
+            large_arc,
+            self.render_coordinate(end_x),
+            self.render_coordinate(end_y),
+            color
+        )
+    }
+
+    fn render_coordinate(&self, value: f64) -> String {
+        self.arithmetic_renderer.render_float(value)
+    }
+
+    fn alignment_class(&self, alignment: Alignment) -> &'static str {
+        match alignment {
+            Alignment::Left => "left",
+            Alignment::Right => "right",
+        }
+    }
+
+    /// Number of a ticker to chart, 0 if unknown.
+    fn figure(&self, figures: &HashMap<Ticker, f64>, ticker: &str) -> f64 {
+        figures.get(&ticker.into()).copied().unwrap_or_default()
+    }
+
+    fn escape(&self, text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            "AT&amp;T &lt;b&gt;",
+            HtmlFormatter::default().escape("AT&T <b>")
+        );
+    }
+
+    #[test]
+    fn format_pie_chart_from_figures() {
+        // Given
+        let formatter = HtmlFormatter::new(Figures {
+            scores: Default::default(),
+            ratios: [("A".into(), 1.0)].into(),
+        });
+        let advice = vec![StockAdvice {
+            ticker: "A".into(),
+            ratio: "All".into(),
+            effective_ratio: None,
+            amount: None,
+            quantity: None,
+        }];
+
+        // When
+        let section = formatter.format_advice_section(&advice, None);
+
+        // Then
+        assert!(section.contains(r##"<circle cx="100" cy="100" r="100" fill="#4e79a7"/>"##));
+        assert!(section.contains(">A 100%</text>"));
+    }
+
+    #[test]
+    fn format_pie_slice_full_circle() {
+        assert_eq!(
+            "<circle cx=\"100\" cy=\"100\" r=\"100\" fill=\"red\"/>\n",
+            HtmlFormatter::default().format_pie_slice(0.0, 1.0, "red")
+        );
+    }
+
+    #[test]
+    fn format_pie_slice_quarter() {
+        assert_eq!(
+            "<path d=\"M 100 100 L 100 0 A 100 100 0 0 1 200 100 Z\" fill=\"red\"/>\n",
+            HtmlFormatter::default().format_pie_slice(0.0, 0.25, "red")
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use super::OutputFormatter;
+use anyhow::Context;
+use schema::Output;
+use schema::Rebalance;
+use schema::StockAdvice;
+use schema::StockReport;
+use serde::Serialize;
+
+/// `Advice.json` in the JSON schema, borrowing the advice instead of copying it.
+#[derive(Serialize)]
+struct AdviceDocument<'a> {
+    advice: &'a [StockAdvice],
+    leftover_cash: Option<&'a str>,
+}
+
+/// Serializes the result following the JSON schema, so that other programs can consume it.
+pub struct JsonFormatter;
+
+impl OutputFormatter for JsonFormatter {
+    fn format_report(&self, report: &[StockReport]) -> anyhow::Result<String> {
+        self.format_json(report)
+    }
+
+    fn format_advice(
+        &self,
+        advice: &[StockAdvice],
+        leftover_cash: Option<&str>,
+    ) -> anyhow::Result<String> {
+        self.format_json(AdviceDocument {
+            advice,
+            leftover_cash,
+        })
+    }
+
+    fn format_rebalance(&self, rebalance: &Rebalance) -> anyhow::Result<String> {
+        self.format_json(rebalance)
+    }
+
+    fn format_output(&self, output: &Output) -> anyhow::Result<String> {
+        self.format_json(output)
+    }
+}
+
+impl JsonFormatter {
+    fn format_json(&self, data: impl Serialize) -> anyhow::Result<String> {
+        let json = serde_json::to_string_pretty(&data)
+            .context("Failed to serialize the output as JSON")?;
+        Ok(json + "\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_advice_with_leftover_cash() {
+        // Given
+        let advice = vec![StockAdvice {
+            ticker: "A".into(),
+            ratio: "100%".into(),
+            effective_ratio: Some("90%".into()),
+            amount: Some("900".into()),
+            quantity: Some("3".into()),
+        }];
+
+        // When
+        let json = JsonFormatter.format_advice(&advice, Some("100")).unwrap();
+
+        // Then
+        let actual: schema::Advice = serde_json::from_str(&json).unwrap();
+        assert_eq!(1, actual.advice.len());
+        assert_eq!(Some("100".to_string()), actual.leftover_cash);
+    }
+}
@@ -0,0 +1,141 @@
+use super::OutputFormatter;
+use crate::table_renderer::Alignment;
+use crate::table_renderer::Table;
+use schema::Rebalance;
+use schema::StockAdvice;
+use schema::StockReport;
+use unicode_width::UnicodeWidthStr;
+
+/// Formats each table in GitHub-flavored Markdown under its own heading.
+pub struct MarkdownFormatter;
+
+impl OutputFormatter for MarkdownFormatter {
+    fn format_report(&self, report: &[StockReport]) -> anyhow::Result<String> {
+        Ok(format!(
+            "## Stock performance report\n\n{}",
+            self.format_table(&Table::from(report))
+        ))
+    }
+
+    fn format_advice(
+        &self,
+        advice: &[StockAdvice],
+        leftover_cash: Option<&str>,
+    ) -> anyhow::Result<String> {
+        Ok(format!(
+            "## Investment advice for this month\n\n{}{}",
+            self.format_table(&Table::from(advice)),
+            leftover_cash.map_or_else(String::default, |cash| {
+                format!("\nLeftover cash: {}\n", self.escape(cash))
+            })
+        ))
+    }
+
+    fn format_rebalance(&self, rebalance: &Rebalance) -> anyhow::Result<String> {
+        Ok(format!(
+            "## Rebalancing advice\n\n{}\nLeftover cash: {}\n",
+            self.format_table(&Table::from(&rebalance.trades[..])),
+            self.escape(&rebalance.leftover_cash)
+        ))
+    }
+}
+
+impl MarkdownFormatter {
+    fn format_table(&self, table: &Table) -> String {
+        let header: Vec<_> = table
+            .columns()
+            .iter()
+            .map(|column| self.escape(column.header()))
+            .collect();
+        let rows: Vec<Vec<_>> = table
+            .rows()
+            .iter()
+            .map(|row| row.iter().map(|cell| self.escape(cell)).collect())
+            .collect();
+        let widths: Vec<_> = header
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                rows.iter()
+                    .filter_map(|row| row.get(index))
+                    .map(|cell| cell.width())
+                    .chain([cell.width(), 3])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let delimiter: Vec<_> = table
+            .columns()
+            .iter()
+            .zip(&widths)
+            .map(|(column, width)| match column.alignment() {
+                Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+            })
+            .collect();
+
+        let mut lines = vec![
+            self.format_row(table, &widths, &header),
+            format!("| {} |", delimiter.join(" | ")),
+        ];
+        lines.extend(rows.iter().map(|row| self.format_row(table, &widths, row)));
+        lines
+            .into_iter()
+            .map(|line| format!("{}\n", line))
+            .collect()
+    }
+
+    fn format_row(&self, table: &Table, widths: &[usize], cells: &[String]) -> String {
+        let padded: Vec<_> = table
+            .columns()
+            .iter()
+            .zip(widths)
+            .zip(cells)
+            .map(|((column, width), cell)| {
+                let padding = " ".repeat(width.saturating_sub(cell.width()));
+                match column.alignment() {
+                    Alignment::Left => format!("{}{}", cell, padding),
+                    Alignment::Right => format!("{}{}", padding, cell),
+                }
+            })
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    }
+
+    fn escape(&self, cell: &str) -> String {
+        cell.replace('|', "\\|")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::table_renderer::Column;
+
+    #[test]
+    fn format_table() {
+        // Given
+        let table = Table::new(
+            vec![
+                Column::new("ticker", Alignment::Left),
+                Column::new("得分", Alignment::Right),
+            ],
+            vec![
+                vec!["A|B".into(), "1.5".into()],
+                vec!["C".into(), "10".into()],
+            ],
+        );
+        let expected = "\
+| ticker | 得分 |
+| :----- | ---: |
+| A\\|B   |  1.5 |
+| C      |   10 |
+";
+
+        // When
+        let actual = MarkdownFormatter.format_table(&table);
+
+        // Then
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,69 @@
+mod csv;
+mod html;
+mod json;
+mod markdown;
+mod powershell;
+mod table;
+
+use self::csv::CsvFormatter;
+use self::html::HtmlFormatter;
+use self::json::JsonFormatter;
+use self::markdown::MarkdownFormatter;
+use self::powershell::PowerShellFormatter;
+use self::table::TableFormatter;
+use crate::cli::OutputFormat;
+use crate::ranker::Ticker;
+use schema::Output;
+use schema::Rebalance;
+use schema::StockAdvice;
+use schema::StockReport;
+use std::collections::HashMap;
+
+/// Unrounded numbers behind the rendered text, for the formats drawing charts.
+#[derive(Default)]
+pub struct Figures {
+    /// Total score of each ticker in the report.
+    pub scores: HashMap<Ticker, f64>,
+
+    /// Ratio of cash of each ticker in the advice.
+    pub ratios: HashMap<Ticker, f64>,
+}
+
+/// Presents the result of a run in one of the [OutputFormat]s.
+pub trait OutputFormatter {
+    fn format_report(&self, report: &[StockReport]) -> anyhow::Result<String>;
+
+    /// Formats the advice, followed by the leftover cash if any.
+    fn format_advice(
+        &self,
+        advice: &[StockAdvice],
+        leftover_cash: Option<&str>,
+    ) -> anyhow::Result<String>;
+
+    /// Formats the trades to rebalance the portfolio, followed by the leftover cash.
+    fn format_rebalance(&self, rebalance: &Rebalance) -> anyhow::Result<String>;
+
+    /// Formats the report followed by the advice.
+    fn format_output(&self, output: &Output) -> anyhow::Result<String> {
+        Ok(format!(
+            "{}\n{}",
+            self.format_report(&output.report)?,
+            self.format_advice(&output.advice, output.leftover_cash.as_deref())?
+        ))
+    }
+}
+
+pub fn new_formatter(
+    format: OutputFormat,
+    border: bool,
+    figures: Figures,
+) -> Box<dyn OutputFormatter> {
+    match format {
+        OutputFormat::Table => Box::new(TableFormatter::new(border)),
+        OutputFormat::PowerShell => Box::new(PowerShellFormatter),
+        OutputFormat::Json => Box::new(JsonFormatter),
+        OutputFormat::Csv => Box::new(CsvFormatter),
+        OutputFormat::Markdown => Box::new(MarkdownFormatter),
+        OutputFormat::Html => Box::new(HtmlFormatter::new(figures)),
+    }
+}
@@ -0,0 +1,67 @@
+use super::OutputFormatter;
+use anyhow::Context;
+use anyhow::anyhow;
+use schema::Rebalance;
+use schema::StockAdvice;
+use schema::StockReport;
+use serde::Serialize;
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+
+/// Delegates to PowerShell's `Format-Table`, kept for those who prefer its look.
+pub struct PowerShellFormatter;
+
+impl OutputFormatter for PowerShellFormatter {
+    fn format_report(&self, report: &[StockReport]) -> anyhow::Result<String> {
+        let table = self.format_json_as_table(report, include_str!("Print-Report.ps1"))?;
+        Ok(format!("Stock performance report:\n{}", table))
+    }
+
+    fn format_advice(
+        &self,
+        advice: &[StockAdvice],
+        leftover_cash: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let table = self.format_json_as_table(advice, include_str!("Print-Advice.ps1"))?;
+        Ok(format!(
+            "Investment advice for this month:\n{}{}",
+            table,
+            leftover_cash.map_or_else(String::default, |cash| {
+                format!("Leftover cash: {}\n", cash)
+            })
+        ))
+    }
+
+    fn format_rebalance(&self, rebalance: &Rebalance) -> anyhow::Result<String> {
+        let table =
+            self.format_json_as_table(&rebalance.trades, include_str!("Print-Rebalance.ps1"))?;
+        Ok(format!(
+            "Rebalancing advice:\n{}Leftover cash: {}\n",
+            table, rebalance.leftover_cash
+        ))
+    }
+}
+
+impl PowerShellFormatter {
+    fn format_json_as_table(&self, data: impl Serialize, script: &str) -> anyhow::Result<String> {
+        let json = serde_json::to_string(&data).context("Failed to serialize the data as JSON")?;
+        let mut process = Command::new("pwsh")
+            .args(["-Command", script])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .context("Failed to launch PowerShell")?;
+        process
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("Failed to get stdin"))?
+            .write_all(json.as_bytes())
+            .context("Failed to write string into PowerShell")?;
+        let output = process
+            .wait_with_output()
+            .context("Failed to wait for PowerShell process")?;
+        String::from_utf8(output.stdout).context("PowerShell printed invalid UTF-8")
+    }
+}
@@ -0,0 +1,50 @@
+use super::OutputFormatter;
+use crate::table_renderer::Table;
+use crate::table_renderer::TableRenderer;
+use schema::Rebalance;
+use schema::StockAdvice;
+use schema::StockReport;
+
+pub struct TableFormatter {
+    table_renderer: TableRenderer,
+}
+
+impl TableFormatter {
+    pub fn new(border: bool) -> Self {
+        Self {
+            table_renderer: TableRenderer::new(border),
+        }
+    }
+}
+
+impl OutputFormatter for TableFormatter {
+    fn format_report(&self, report: &[StockReport]) -> anyhow::Result<String> {
+        Ok(format!(
+            "Stock performance report:\n{}",
+            self.table_renderer.render(&Table::from(report))
+        ))
+    }
+
+    fn format_advice(
+        &self,
+        advice: &[StockAdvice],
+        leftover_cash: Option<&str>,
+    ) -> anyhow::Result<String> {
+        Ok(format!(
+            "Investment advice for this month:\n{}{}",
+            self.table_renderer.render(&Table::from(advice)),
+            leftover_cash.map_or_else(String::default, |cash| {
+                format!("Leftover cash: {}\n", cash)
+            })
+        ))
+    }
+
+    fn format_rebalance(&self, rebalance: &Rebalance) -> anyhow::Result<String> {
+        Ok(format!(
+            "Rebalancing advice:\n{}Leftover cash: {}\n",
+            self.table_renderer
+                .render(&Table::from(&rebalance.trades[..])),
+            rebalance.leftover_cash
+        ))
+    }
+}
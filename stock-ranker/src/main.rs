@@ -1,78 +1,344 @@
 mod advisor;
 mod arithmetic_renderer;
+mod cli;
+mod config;
+mod explainer;
+mod formatter;
+mod ordering;
 mod ranker;
 mod report;
+mod sanitizer;
 mod scoring_candidate;
+mod settings;
+mod table_renderer;
+mod validator;
 
+use crate::advisor::Advice;
+use crate::advisor::Budget;
 use crate::advisor::InvestAdvisor;
+use crate::advisor::Portfolio;
+use crate::cli::Cli;
+use crate::cli::Command;
+use crate::config::Config;
+use crate::config::DEFAULT_CONFIG_PATH;
+use crate::explainer::Explainer;
+use crate::formatter::Figures;
+use crate::formatter::new_formatter;
+use crate::ordering::StockOrdering;
+use crate::ranker::Score;
+use crate::ranker::ScoreBreakdown;
 use crate::ranker::StockRanker;
+use crate::ranker::Ticker;
 use crate::report::ReportRenderer;
+use crate::sanitizer::CandidateSanitizer;
 use crate::scoring_candidate::ScoringCandidateExtractor;
+use crate::settings::Settings;
+use crate::settings::SettingsLoader;
+use crate::validator::MetricValidator;
 use anyhow::Context;
-use anyhow::anyhow;
+use clap::Parser;
+use schema::Holding;
 use schema::Output;
 use schema::ProductMetric;
-use serde::Serialize;
+use schema::Rebalance;
+use schema::StockReport;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
 use std::io::Write;
 use std::io::stdin;
-use std::process::Command;
-use std::process::Stdio;
-use std::str::FromStr;
+use std::io::stdout;
+use std::path::Path;
 
 fn main() -> anyhow::Result<()> {
-    let input: Vec<ProductMetric> =
-        serde_json::from_reader(stdin()).context("Failed to deserialize the input as JSON")?;
-    let output = rank(input)?;
+    let cli = Cli::parse();
+    let settings = SettingsLoader::from_env().load(&cli)?;
+    let input = read_metrics(cli.input.as_deref())?;
+    let issues = MetricValidator.validate(&input);
+    if let Some(Command::Validate) = cli.command {
+        let text = if issues.is_empty() {
+            format!("No issue found in {} entries\n", input.len())
+        } else {
+            MetricValidator.render(&issues)
+        };
+        write_output(cli.output.as_deref(), &text)?;
+        return MetricValidator.check(&issues, settings.strict.value);
+    }
+    eprint!("{}", MetricValidator.render(&issues));
+    if settings.strict.value {
+        MetricValidator.check(&issues, true)?;
+    }
+    let input = MetricValidator.filter(input, &issues);
+    let config = load_config(&settings)?;
+    let input: Vec<_> = input
+        .into_iter()
+        .filter(|metric| config.filter.accepts(metric))
+        .collect();
+    let portfolio = match &cli.command {
+        Some(Command::Rebalance { holdings, buy_only }) => Some(Portfolio {
+            holdings: read_holdings(holdings)?,
+            buy_only: *buy_only,
+        }),
+        _ => None,
+    };
+    let ranking = rank(input, &settings, &config, portfolio.as_ref())?;
 
-    println!("Stock performance report:");
-    print_json_as_table(&output.report, include_str!("Print-Report.ps1"))?;
-    println!("Investment advice for this month:");
-    print_json_as_table(&output.advice, include_str!("Print-Advice.ps1"))?;
+    let figures = Figures {
+        scores: ranking
+            .breakdowns
+            .iter()
+            .map(|(ticker, breakdown)| (ticker.clone(), breakdown.total().value))
+            .collect(),
+        ratios: ranking
+            .advice
+            .as_ref()
+            .map(|advice| advice.ratios.clone())
+            .unwrap_or_default(),
+    };
+    let formatter = new_formatter(settings.format.value, settings.border.value, figures);
+    let text = match cli.command.unwrap_or_default() {
+        Command::Rank => {
+            let advice = ranking.advice?;
+            formatter.format_output(&Output {
+                report: ranking.report,
+                advice: advice.entries,
+                leftover_cash: advice.leftover_cash,
+            })?
+        }
+        Command::Report => formatter.format_report(&ranking.report)?,
+        Command::Advise => {
+            let advice = ranking.advice?;
+            formatter.format_advice(&advice.entries, advice.leftover_cash.as_deref())?
+        }
+        Command::Explain { ticker } => Explainer::default().explain(
+            &ticker.into(),
+            &ranking.breakdowns,
+            &ranking.sorted_scores,
+            ranking
+                .advice
+                .as_ref()
+                .map(|advice| advice.entries.as_slice()),
+            ranking.skip_count,
+            ranking.invest_count,
+        )?,
+        Command::Rebalance { .. } => match ranking.rebalance {
+            Some(rebalance) => formatter.format_rebalance(&rebalance?)?,
+            None => unreachable!("Rebalancing is computed for the rebalance command"),
+        },
+        Command::Validate => unreachable!("Validation returns early"),
+    };
+    write_output(cli.output.as_deref(), &text)
+}
 
-    Ok(())
+fn read_metrics(path: Option<&Path>) -> anyhow::Result<Vec<ProductMetric>> {
+    let reader: Box<dyn Read> = match path {
+        Some(path) => Box::new(
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+        ),
+        None => Box::new(stdin()),
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .context("Failed to deserialize the input as JSON")
 }
 
-fn rank(metrics: Vec<ProductMetric>) -> anyhow::Result<Output> {
+fn read_holdings(path: &Path) -> anyhow::Result<Vec<Holding>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut deserializer = serde_json::Deserializer::from_reader(file);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .with_context(|| format!("Failed to deserialize {} as holdings", path.display()))
+}
+
+fn load_config(settings: &Settings) -> anyhow::Result<Config> {
+    if let Some(path) = &settings.config {
+        return Config::load(&path.value);
+    }
+    let default_path = Path::new(DEFAULT_CONFIG_PATH);
+    if default_path.exists() {
+        Config::load(default_path)
+    } else {
+        Ok(Config::default())
+    }
+}
+
+fn write_output(path: Option<&Path>, text: &str) -> anyhow::Result<()> {
+    match path {
+        Some(path) => std::fs::write(path, text)
+            .with_context(|| format!("Failed to write to {}", path.display())),
+        None => stdout()
+            .write_all(text.as_bytes())
+            .context("Failed to write to stdout"),
+    }
+}
+
+/// Everything computed in a run.
+struct Ranking {
+    breakdowns: HashMap<Ticker, ScoreBreakdown>,
+    sorted_scores: Vec<(Ticker, Score)>,
+    skip_count: usize,
+    invest_count: usize,
+    report: Vec<StockReport>,
+
+    /// Fails if the caps can't be met, which only aborts the commands that need the advice.
+    advice: anyhow::Result<Advice>,
+
+    /// Absent unless rebalancing a portfolio.
+    rebalance: Option<anyhow::Result<Rebalance>>,
+}
+
+fn rank(
+    metrics: Vec<ProductMetric>,
+    settings: &Settings,
+    config: &Config,
+    portfolio: Option<&Portfolio>,
+) -> anyhow::Result<Ranking> {
     if metrics.is_empty() {
         anyhow::bail!("No stock metric in the input")
     }
+    let prices: HashMap<Ticker, f64> = metrics
+        .iter()
+        .filter_map(|metric| Some((metric.ticker.as_str().into(), metric.price?)))
+        .collect();
+    let budget = |cash: f64| Budget {
+        cash,
+        prices: prices.clone(),
+        fractional_shares: settings.fractional_shares.value,
+    };
 
-    let default_skip_count = 0;
-    let skip_count = std::env::var("STOCK_RANKER_SKIP_COUNT")
-        .ok()
-        .map(|text| usize::from_str(&text).unwrap_or(default_skip_count))
-        .unwrap_or(default_skip_count);
-
-    let default_invest_count = metrics.len() / 2;
-    let invest_count = std::env::var("STOCK_RANKER_INVEST_COUNT")
-        .ok()
-        .map(|text| usize::from_str(&text).unwrap_or(default_invest_count))
-        .unwrap_or(default_invest_count);
-
-    let candidates = ScoringCandidateExtractor.extract_scoring_candidates(&metrics);
-    let scores = StockRanker::default().rank(&candidates);
-    let report = ReportRenderer::default().render(&candidates, &scores);
-    let advice = InvestAdvisor::default().render_advice(&scores, skip_count, invest_count);
-    Ok(Output { report, advice })
+    let mut candidates = ScoringCandidateExtractor.extract_scoring_candidates(&metrics);
+    for diagnostic in CandidateSanitizer.sanitize(&mut candidates) {
+        eprintln!("Warning: {}", diagnostic);
+    }
+    let breakdowns = StockRanker::new(config)?.rank(&candidates);
+    // Tickers excluded for missing factors don't count toward the investment window.
+    let skip_count = settings.skip_count.value;
+    let invest_count = settings.resolve_invest_count(breakdowns.len())?;
+    let scores = breakdowns
+        .iter()
+        .map(|(ticker, breakdown)| (ticker.clone(), breakdown.total()))
+        .collect();
+    let ordering = StockOrdering::new(config.tie_breakers.clone());
+    let sorted_scores = ordering.sort(&candidates, &scores);
+    let report = ReportRenderer::new(ordering.clone()).render(&candidates, &breakdowns);
+    let advisor = InvestAdvisor::new(ordering, config.allocation, config.caps.clone())?;
+    let advice = advisor.render_advice(
+        &candidates,
+        &scores,
+        skip_count,
+        invest_count,
+        settings.cash.map(|cash| budget(cash.value)).as_ref(),
+    );
+    let rebalance = portfolio.map(|portfolio| {
+        advisor.rebalance(
+            &candidates,
+            &scores,
+            skip_count,
+            invest_count,
+            portfolio,
+            &budget(settings.cash.map_or(0.0, |cash| cash.value)),
+        )
+    });
+    Ok(Ranking {
+        breakdowns,
+        sorted_scores,
+        skip_count,
+        invest_count,
+        report,
+        advice,
+        rebalance,
+    })
 }
 
-fn print_json_as_table(data: impl Serialize, script: &str) -> anyhow::Result<()> {
-    let json = serde_json::to_string(&data).context("Failed to serialize the data as JSON")?;
-    let mut process = Command::new("pwsh")
-        .args(["-Command", script])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .context("Failed to launch PowerShell")?;
-    process
-        .stdin
-        .take()
-        .ok_or_else(|| anyhow!("Failed to get stdin"))?
-        .write_all(json.as_bytes())
-        .context("Failed to write string into PowerShell")?;
-    process
-        .wait()
-        .context("Failed to wait for PowerShell process")?;
-    Ok(())
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Caps;
+    use crate::config::MissingFactorPolicy;
+    use crate::config::RankerConfig;
+    use crate::config::RankerKind;
+    use crate::scoring_candidate::ScoringFactor;
+
+    #[test]
+    fn reject_window_exceeding_ranked_tickers() {
+        // Given
+        let metrics: Vec<ProductMetric> = serde_json::from_str(
+            r#"[
+                { "ticker": "A", "name": "A", "one_month_price_change": 0.1 },
+                { "ticker": "B", "name": "B" },
+                { "ticker": "C", "name": "C" },
+                { "ticker": "D", "name": "D" }
+            ]"#,
+        )
+        .unwrap();
+        let settings = SettingsLoader::new(HashMap::default())
+            .load(&Cli::parse_from([
+                "stock-ranker",
+                "advise",
+                "--invest-count",
+                "3",
+            ]))
+            .unwrap();
+        let config = Config {
+            rankers: vec![RankerConfig {
+                kind: RankerKind::OrdinalGreatestWinning,
+                factor: ScoringFactor::OneMonthPriceChange,
+            }],
+            weights: [(ScoringFactor::OneMonthPriceChange, 1.0)].into(),
+            missing_factors: MissingFactorPolicy::Exclude,
+            ..Default::default()
+        };
+
+        // When
+        let error = rank(metrics, &settings, &config, None).err().unwrap();
+
+        // Then
+        assert!(
+            error
+                .to_string()
+                .contains("exceeds the number of ranked tickers 1")
+        );
+    }
+
+    #[test]
+    fn rank_with_infeasible_caps() {
+        // Given
+        let metrics: Vec<ProductMetric> = serde_json::from_str(
+            r#"[
+                { "ticker": "A", "name": "A", "one_month_price_change": 0.1 },
+                { "ticker": "B", "name": "B", "one_month_price_change": 0.2 }
+            ]"#,
+        )
+        .unwrap();
+        let settings = SettingsLoader::new(HashMap::default())
+            .load(&Cli::parse_from([
+                "stock-ranker",
+                "report",
+                "--invest-count",
+                "1",
+            ]))
+            .unwrap();
+        let config = Config {
+            rankers: vec![RankerConfig {
+                kind: RankerKind::OrdinalGreatestWinning,
+                factor: ScoringFactor::OneMonthPriceChange,
+            }],
+            weights: [(ScoringFactor::OneMonthPriceChange, 1.0)].into(),
+            caps: Caps {
+                min_ratio: None,
+                max_ratio: Some(0.5),
+                tickers: HashMap::default(),
+            },
+            ..Default::default()
+        };
+
+        // When
+        let ranking = rank(metrics, &settings, &config, None).unwrap();
+
+        // Then
+        assert_eq!(2, ranking.report.len());
+        assert_eq!(
+            "Caps of the 1 tickers to invest in add up to 0% to 50%, which excludes 100%; invest in more tickers or raise max_ratio",
+            ranking.advice.err().unwrap().to_string()
+        );
+    }
 }
@@ -0,0 +1,162 @@
+use crate::config::TieBreaker;
+use crate::ranker::Score;
+use crate::ranker::Ticker;
+use crate::scoring_candidate::ScoringCandidates;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Sorts tickers by score descendingly, breaking ties deterministically.
+#[derive(Default, Clone)]
+pub struct StockOrdering {
+    tie_breakers: Vec<TieBreaker>,
+}
+
+impl StockOrdering {
+    /// The tickers themselves are always compared after all `tie_breakers`, so that no two tickers tie.
+    pub fn new(tie_breakers: Vec<TieBreaker>) -> Self {
+        Self { tie_breakers }
+    }
+
+    pub fn sort(
+        &self,
+        candidates: &ScoringCandidates,
+        scores: &HashMap<Ticker, Score>,
+    ) -> Vec<(Ticker, Score)> {
+        let mut entries: Vec<_> = scores
+            .iter()
+            .map(|(ticker, score)| (ticker.clone(), *score))
+            .collect();
+        entries.sort_by(|(ticker_a, score_a), (ticker_b, score_b)| {
+            score_b
+                .value
+                .total_cmp(&score_a.value)
+                .then_with(|| self.break_tie(candidates, ticker_a, ticker_b))
+        });
+        entries
+    }
+
+    fn break_tie(&self, candidates: &ScoringCandidates, a: &Ticker, b: &Ticker) -> Ordering {
+        self.tie_breakers
+            .iter()
+            .map(|tie_breaker| self.compare(tie_breaker, candidates, a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.to_string().cmp(&b.to_string()))
+    }
+
+    fn compare(
+        &self,
+        tie_breaker: &TieBreaker,
+        candidates: &ScoringCandidates,
+        a: &Ticker,
+        b: &Ticker,
+    ) -> Ordering {
+        match tie_breaker {
+            TieBreaker::Ticker => a.to_string().cmp(&b.to_string()),
+            TieBreaker::Name => {
+                self.compare_present(candidates.get_name(a), candidates.get_name(b), |x, y| {
+                    x.cmp(y)
+                })
+            }
+            TieBreaker::Factor { factor, ascending } => {
+                let value = |ticker| {
+                    candidates
+                        .get(ticker)
+                        .and_then(|factors| factors.get(factor))
+                        .map(|notional| notional.value)
+                };
+                self.compare_present(value(a), value(b), |x, y| {
+                    if *ascending {
+                        x.total_cmp(&y)
+                    } else {
+                        y.total_cmp(&x)
+                    }
+                })
+            }
+        }
+    }
+
+    /// Compares 2 optional values, putting absent ones last.
+    fn compare_present<T>(
+        &self,
+        a: Option<T>,
+        b: Option<T>,
+        compare: impl Fn(T, T) -> Ordering,
+    ) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) => compare(a, b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ranker::Notional;
+    use crate::scoring_candidate::ScoringFactor;
+
+    fn sorted_tickers(ordering: &StockOrdering, candidates: &ScoringCandidates) -> Vec<String> {
+        let scores: HashMap<_, _> = [
+            ("A".into(), 1.0.into()),
+            ("B".into(), 2.0.into()),
+            ("C".into(), 1.0.into()),
+            ("D".into(), 1.0.into()),
+        ]
+        .into();
+        ordering
+            .sort(candidates, &scores)
+            .into_iter()
+            .map(|(ticker, _)| ticker.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn sort_by_ticker_by_default() {
+        let actual = sorted_tickers(&StockOrdering::default(), &Default::default());
+        assert_eq!(vec!["B", "A", "C", "D"], actual);
+    }
+
+    #[test]
+    fn sort_by_factor() {
+        // Given
+        let candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::LongTermTotalReturn, Notional::from(0.1))]),
+            ),
+            (
+                "C",
+                HashMap::from([(ScoringFactor::LongTermTotalReturn, Notional::from(0.5))]),
+            ),
+        ]
+        .into();
+        let ordering = StockOrdering::new(vec![TieBreaker::Factor {
+            factor: ScoringFactor::LongTermTotalReturn,
+            ascending: false,
+        }]);
+
+        // When
+        let actual = sorted_tickers(&ordering, &candidates);
+
+        // Then
+        assert_eq!(vec!["B", "C", "A", "D"], actual);
+    }
+
+    #[test]
+    fn sort_by_name() {
+        // Given
+        let mut candidates = ScoringCandidates::default();
+        candidates.set_name("A".into(), "Zeta".into());
+        candidates.set_name("C".into(), "Alpha".into());
+        candidates.set_name("D".into(), "Mu".into());
+        let ordering = StockOrdering::new(vec![TieBreaker::Name]);
+
+        // When
+        let actual = sorted_tickers(&ordering, &candidates);
+
+        // Then
+        assert_eq!(vec!["B", "C", "D", "A"], actual);
+    }
+}
@@ -0,0 +1,130 @@
+use super::FactorRanker;
+use super::Score;
+use super::ScoringCandidates;
+use super::Ticker;
+use crate::config::RankerKind;
+use crate::scoring_candidate::ScoringFactor;
+use std::collections::HashMap;
+
+#[mockall_double::double]
+use super::notional_ranker::NotionalRanker;
+
+/// Rescales values of both signs linearly so that the worst one gets nothing and the best one gets the most.
+pub struct MinMaxRanker {
+    notional_ranker: NotionalRanker,
+    factor_type: ScoringFactor,
+    greatest_winning: bool,
+}
+
+impl MinMaxRanker {
+    pub fn new(factor_type: ScoringFactor, greatest_winning: bool) -> Self {
+        Self {
+            notional_ranker: Default::default(),
+            factor_type,
+            greatest_winning,
+        }
+    }
+}
+
+impl FactorRanker for MinMaxRanker {
+    /// Returns no score at all if the values are all the same, since none beats another.
+    fn rank(&self, candidates: &ScoringCandidates) -> HashMap<Ticker, Score> {
+        let values: Vec<_> = candidates
+            .iter()
+            .filter_map(|(ticker, factors)| {
+                factors
+                    .get(&self.factor_type)
+                    .filter(|notional| notional.value.is_finite())
+                    .map(|notional| (ticker, notional.value))
+            })
+            .collect();
+        let min = values
+            .iter()
+            .map(|(_, value)| *value)
+            .fold(f64::INFINITY, f64::min);
+        let max = values
+            .iter()
+            .map(|(_, value)| *value)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let range = max - min;
+        if range == 0.0 || !range.is_finite() {
+            return HashMap::default();
+        }
+        let notional_candidates: HashMap<_, _> = values
+            .into_iter()
+            .map(|(ticker, value)| {
+                let distance = if self.greatest_winning {
+                    value - min
+                } else {
+                    max - value
+                };
+                (ticker.clone(), (distance / range).into())
+            })
+            .collect();
+        self.notional_ranker.rank(&notional_candidates)
+    }
+    fn get_factor(&self) -> ScoringFactor {
+        self.factor_type
+    }
+    fn get_kind(&self) -> RankerKind {
+        if self.greatest_winning {
+            RankerKind::MinMaxGreatestWinning
+        } else {
+            RankerKind::MinMaxLeastWinning
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ranker::Notional;
+    use test_case::case;
+
+    #[case(true, [0.0, 0.75, 1.0])]
+    #[case(false, [1.0, 0.25, 0.0])]
+    fn rank_correct_candidates(greatest_winning: bool, expected_notionals: [f64; 3]) {
+        // Given
+        let stock_candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(-0.5))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.25))]),
+            ),
+            (
+                "C",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.5))]),
+            ),
+            (
+                "D",
+                HashMap::from([(ScoringFactor::LongTermTotalReturn, Notional::from(1.0))]),
+            ),
+        ]
+        .into();
+        let expected_notional_candidates: HashMap<_, _> = ["A", "B", "C"]
+            .into_iter()
+            .zip(expected_notionals)
+            .map(|(ticker, notional)| (ticker.into(), notional.into()))
+            .collect();
+        let expected_scores: HashMap<_, _> = [("B".into(), 1.0.into())].into();
+        let mut notional_ranker = NotionalRanker::default();
+        notional_ranker
+            .expect_rank()
+            .withf_st(move |arg| arg == &expected_notional_candidates)
+            .return_const_st(expected_scores.clone());
+        let ranker = MinMaxRanker {
+            notional_ranker,
+            factor_type: ScoringFactor::OneMonthPriceChange,
+            greatest_winning,
+        };
+
+        // When
+        let actual_scores = ranker.rank(&stock_candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+}
@@ -1,65 +1,183 @@
+mod min_max_ranker;
 mod negative_least_winning_ranker;
 mod notional_ranker;
+mod ordinal_ranker;
 mod positive_greatest_winning_ranker;
+mod positive_least_winning_ranker;
+mod z_score_ranker;
 
+use self::min_max_ranker::MinMaxRanker;
 use self::negative_least_winning_ranker::NegativeLeastWinningRanker;
+use self::ordinal_ranker::OrdinalRanker;
 use self::positive_greatest_winning_ranker::PositiveGreatestWinningRanker;
+use self::positive_least_winning_ranker::PositiveLeastWinningRanker;
+use self::z_score_ranker::ZScoreRanker;
+use crate::config::Config;
+use crate::config::MissingFactorPolicy;
+use crate::config::RankerConfig;
+use crate::config::RankerKind;
 use crate::scoring_candidate::ScoringCandidates;
 use crate::scoring_candidate::ScoringFactor;
 use derive_more::Add;
 use derive_more::Display;
 use derive_more::From;
 use derive_more::Mul;
-use itertools::Itertools;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 pub struct StockRanker {
     rankers: Vec<Box<dyn FactorRanker>>,
     factor_weight: HashMap<ScoringFactor, f64>,
+    missing_factors: MissingFactorPolicy,
 }
 
-impl Default for StockRanker {
-    fn default() -> Self {
-        Self {
-            rankers: vec![
-                Box::new(NegativeLeastWinningRanker::new(
-                    ScoringFactor::OneMonthPriceChange,
-                )),
-                Box::new(PositiveGreatestWinningRanker::new(
-                    ScoringFactor::LongTermTotalReturn,
-                )),
-            ],
-            factor_weight: HashMap::from([
-                (ScoringFactor::OneMonthPriceChange, 6.0),
-                (ScoringFactor::LongTermTotalReturn, 4.0),
-            ]),
+impl StockRanker {
+    /// Builds the ranker pipeline declared in a [Config], making sure every factor in use is weighted.
+    pub fn new(config: &Config) -> anyhow::Result<Self> {
+        if config.rankers.is_empty() {
+            anyhow::bail!("No ranker is configured")
         }
+        for ranker in config.rankers.iter() {
+            if !config.weights.contains_key(&ranker.factor) {
+                anyhow::bail!(
+                    "No weight registered for factor {} used by ranker {}",
+                    ranker.factor,
+                    ranker.kind
+                )
+            }
+        }
+        for (factor, weight) in config.weights.iter() {
+            if !weight.is_finite() || *weight < 0.0 {
+                anyhow::bail!(
+                    "Weight {} of factor {} is not a non-negative number",
+                    weight,
+                    factor
+                )
+            }
+            if !config.rankers.iter().any(|ranker| &ranker.factor == factor) {
+                anyhow::bail!("Weight registered for factor {} used by no ranker", factor)
+            }
+        }
+        Ok(Self {
+            rankers: config.rankers.iter().map(build_factor_ranker).collect(),
+            factor_weight: config.weights.clone(),
+            missing_factors: config.missing_factors,
+        })
     }
-}
 
-impl StockRanker {
-    pub fn rank(&self, candidates: &ScoringCandidates) -> HashMap<Ticker, Score> {
-        self.rankers
+    /// Scores each ticker, keeping track of how much each ranker contributes.
+    ///
+    /// Tickers excluded by the [MissingFactorPolicy] are left out of the result.
+    pub fn rank(&self, candidates: &ScoringCandidates) -> HashMap<Ticker, ScoreBreakdown> {
+        let ranked_candidates = self.select_candidates(candidates);
+        let ranker_scores: Vec<_> = self
+            .rankers
+            .iter()
+            .map(|ranker| (ranker.as_ref(), ranker.rank(&ranked_candidates)))
+            .collect();
+        let tickers: HashSet<_> = ranked_candidates
             .iter()
-            .flat_map(|ranker| self.calculate_weighted_rank(ranker.as_ref(), candidates))
-            .into_grouping_map()
-            .sum()
+            .map(|(ticker, _)| ticker)
+            .chain(ranker_scores.iter().flat_map(|(_, scores)| scores.keys()))
+            .cloned()
+            .collect();
+        tickers
+            .into_iter()
+            .map(|ticker| {
+                let mut contributions: Vec<_> = ranker_scores
+                    .iter()
+                    .map(|(ranker, scores)| {
+                        self.build_contribution(*ranker, candidates, &ticker, scores.get(&ticker))
+                    })
+                    .collect();
+                self.rescale_weights(&mut contributions);
+                (ticker, ScoreBreakdown { contributions })
+            })
+            .collect()
+    }
+
+    /// Leaves out the tickers lacking any factor in use if the policy excludes them.
+    fn select_candidates<'a>(
+        &self,
+        candidates: &'a ScoringCandidates,
+    ) -> Cow<'a, ScoringCandidates> {
+        if self.missing_factors != MissingFactorPolicy::Exclude {
+            return Cow::Borrowed(candidates);
+        }
+        let mut selected = candidates.clone();
+        selected.retain_tickers(|_, factors| {
+            self.rankers
+                .iter()
+                .all(|ranker| factors.contains_key(&ranker.get_factor()))
+        });
+        Cow::Owned(selected)
+    }
+
+    /// Spreads the weights of the missing factors over the present ones if the policy rescales them.
+    fn rescale_weights(&self, contributions: &mut [FactorContribution]) {
+        if self.missing_factors != MissingFactorPolicy::Rescale {
+            return;
+        }
+        let total_weight: f64 = contributions.iter().map(|c| c.weight).sum();
+        let present_weight: f64 = contributions
+            .iter()
+            .filter(|c| c.notional.is_some())
+            .map(|c| c.weight)
+            .sum();
+        if present_weight <= 0.0 {
+            return;
+        }
+        for contribution in contributions.iter_mut() {
+            if contribution.notional.is_some() {
+                contribution.weight *= total_weight / present_weight;
+            }
+        }
     }
-    fn calculate_weighted_rank(
+
+    fn build_contribution(
         &self,
         ranker: &dyn FactorRanker,
         candidates: &ScoringCandidates,
-    ) -> HashMap<Ticker, Score> {
-        let weight = self
+        ticker: &Ticker,
+        score: Option<&Score>,
+    ) -> FactorContribution {
+        let factor = ranker.get_factor();
+        let weight = *self
             .factor_weight
-            .get(&ranker.get_factor())
-            .unwrap_or_else(|| panic!("No weight registered for factor {:?}", ranker.get_factor()));
-        ranker
-            .rank(candidates)
-            .into_iter()
-            .map(|(ticker, score)| (ticker, score * weight))
-            .collect()
+            .get(&factor)
+            .unwrap_or_else(|| panic!("No weight registered for factor {:?}", factor));
+        FactorContribution {
+            factor,
+            ranker: ranker.get_kind(),
+            notional: candidates
+                .get(ticker)
+                .and_then(|factors| factors.get(&factor))
+                .copied(),
+            score: score.copied(),
+            weight,
+        }
+    }
+}
+
+fn build_factor_ranker(config: &RankerConfig) -> Box<dyn FactorRanker> {
+    match config.kind {
+        RankerKind::NegativeLeastWinning => {
+            Box::new(NegativeLeastWinningRanker::new(config.factor))
+        }
+        RankerKind::PositiveGreatestWinning => {
+            Box::new(PositiveGreatestWinningRanker::new(config.factor))
+        }
+        RankerKind::PositiveLeastWinning => {
+            Box::new(PositiveLeastWinningRanker::new(config.factor))
+        }
+        RankerKind::OrdinalGreatestWinning => Box::new(OrdinalRanker::new(config.factor, true)),
+        RankerKind::OrdinalLeastWinning => Box::new(OrdinalRanker::new(config.factor, false)),
+        RankerKind::ZScoreGreatestWinning => Box::new(ZScoreRanker::new(config.factor, true)),
+        RankerKind::ZScoreLeastWinning => Box::new(ZScoreRanker::new(config.factor, false)),
+        RankerKind::MinMaxGreatestWinning => Box::new(MinMaxRanker::new(config.factor, true)),
+        RankerKind::MinMaxLeastWinning => Box::new(MinMaxRanker::new(config.factor, false)),
     }
 }
 
@@ -67,6 +185,43 @@ impl StockRanker {
 trait FactorRanker {
     fn rank(&self, candidates: &ScoringCandidates) -> HashMap<Ticker, Score>;
     fn get_factor(&self) -> ScoringFactor;
+    fn get_kind(&self) -> RankerKind;
+}
+
+/// Score of a ticker split by the rankers it comes from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoreBreakdown {
+    pub contributions: Vec<FactorContribution>,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> Score {
+        self.contributions
+            .iter()
+            .map(FactorContribution::weighted_score)
+            .fold(Score::default(), |x, y| x + y)
+    }
+}
+
+/// What a ranker thinks of a ticker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorContribution {
+    pub factor: ScoringFactor,
+    pub ranker: RankerKind,
+
+    /// Raw value of the factor, absent if the ticker doesn't have it.
+    pub notional: Option<Notional>,
+
+    /// Normalized score given by the ranker, absent if the ranker filtered out the ticker.
+    pub score: Option<Score>,
+
+    pub weight: f64,
+}
+
+impl FactorContribution {
+    pub fn weighted_score(&self) -> Score {
+        self.score.unwrap_or_default() * self.weight
+    }
 }
 
 /// Code name of a stock.
@@ -104,6 +259,74 @@ pub struct Score {
 #[cfg(test)]
 mod test {
     use super::*;
+    use test_case::case;
+
+    /// Gives a score of 1 to every candidate having the factor.
+    fn mock_ranker(factor: ScoringFactor) -> MockFactorRanker {
+        let mut ranker = MockFactorRanker::default();
+        ranker
+            .expect_rank()
+            .returning_st(move |candidates: &ScoringCandidates| {
+                candidates
+                    .iter()
+                    .filter(|(_, factors)| factors.contains_key(&factor))
+                    .map(|(ticker, _)| (ticker.clone(), 1.0.into()))
+                    .collect()
+            });
+        ranker.expect_get_factor().return_const_st(factor);
+        ranker
+            .expect_get_kind()
+            .return_const_st(RankerKind::PositiveGreatestWinning);
+        ranker
+    }
+
+    #[case(MissingFactorPolicy::Zero, 4.0, Some(1.0))]
+    #[case(MissingFactorPolicy::Rescale, 4.0, Some(4.0))]
+    #[case(MissingFactorPolicy::Exclude, 4.0, None)]
+    fn rank_missing_factors(policy: MissingFactorPolicy, expected_a: f64, expected_b: Option<f64>) {
+        // Given
+        let candidates: ScoringCandidates = [
+            (
+                "A",
+                [
+                    (ScoringFactor::LongTermTotalReturn, 0.5.into()),
+                    (ScoringFactor::OneMonthPriceChange, (-0.1).into()),
+                ]
+                .into(),
+            ),
+            (
+                "B",
+                [(ScoringFactor::OneMonthPriceChange, (-0.3).into())].into(),
+            ),
+        ]
+        .into();
+        let service = StockRanker {
+            rankers: vec![
+                Box::new(mock_ranker(ScoringFactor::LongTermTotalReturn)),
+                Box::new(mock_ranker(ScoringFactor::OneMonthPriceChange)),
+            ],
+            factor_weight: HashMap::from([
+                (ScoringFactor::LongTermTotalReturn, 3.0),
+                (ScoringFactor::OneMonthPriceChange, 1.0),
+            ]),
+            missing_factors: policy,
+        };
+        let expected_scores: HashMap<_, _> =
+            [("A".into(), Some(expected_a)), ("B".into(), expected_b)]
+                .into_iter()
+                .filter_map(|(ticker, score)| Some((ticker, score?.into())))
+                .collect();
+
+        // When
+        let actual_scores: HashMap<_, _> = service
+            .rank(&candidates)
+            .into_iter()
+            .map(|(ticker, breakdown)| (ticker, breakdown.total()))
+            .collect();
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
 
     #[test]
     fn sum_scores() {
@@ -113,6 +336,9 @@ mod test {
         ranker1
             .expect_get_factor()
             .return_const_st(ScoringFactor::LongTermTotalReturn);
+        ranker1
+            .expect_get_kind()
+            .return_const_st(RankerKind::PositiveGreatestWinning);
 
         let score2: HashMap<_, _> = [("A".into(), 300.0.into())].into();
         let mut ranker2 = MockFactorRanker::default();
@@ -120,6 +346,9 @@ mod test {
         ranker2
             .expect_get_factor()
             .return_const_st(ScoringFactor::OneMonthPriceChange);
+        ranker2
+            .expect_get_kind()
+            .return_const_st(RankerKind::NegativeLeastWinning);
 
         let expected_scores: HashMap<_, _> =
             [("A".into(), 70.0.into()), ("B".into(), 20.0.into())].into();
@@ -129,12 +358,54 @@ mod test {
                 (ScoringFactor::LongTermTotalReturn, 0.1),
                 (ScoringFactor::OneMonthPriceChange, 0.2),
             ]),
+            missing_factors: Default::default(),
         };
 
         // When
-        let actual_scores = service.rank(&Default::default());
+        let actual_scores: HashMap<_, _> = service
+            .rank(&Default::default())
+            .into_iter()
+            .map(|(ticker, breakdown)| (ticker, breakdown.total()))
+            .collect();
 
         // Then
         assert_eq!(expected_scores, actual_scores);
     }
+
+    #[test]
+    fn new_from_default_config() {
+        assert!(StockRanker::new(&Config::default()).is_ok());
+    }
+
+    #[test]
+    fn new_without_weight() {
+        // Given
+        let mut config = Config::default();
+        config.weights.remove(&ScoringFactor::LongTermTotalReturn);
+
+        // When
+        let error = StockRanker::new(&config).err().unwrap().to_string();
+
+        // Then
+        assert_eq!(
+            "No weight registered for factor long_term_total_return used by ranker positive_greatest_winning",
+            error
+        );
+    }
+
+    #[test]
+    fn new_with_unused_weight() {
+        // Given
+        let mut config = Config::default();
+        config.rankers.pop();
+
+        // When
+        let error = StockRanker::new(&config).err().unwrap().to_string();
+
+        // Then
+        assert_eq!(
+            "Weight registered for factor long_term_total_return used by no ranker",
+            error
+        );
+    }
 }
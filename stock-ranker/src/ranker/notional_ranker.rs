@@ -8,14 +8,23 @@ pub struct NotionalRanker;
 
 #[mockall::automock]
 impl NotionalRanker {
+    /// Scores each candidate by its share of the total, ignoring non-finite notionals.
+    ///
+    /// Returns no score at all if the total is 0 or not finite, since no share can be told then.
     pub fn rank(&self, candidates: &HashMap<Ticker, Notional>) -> HashMap<Ticker, Score> {
-        let total_notional = candidates
-            .values()
-            .map(|x| x.value)
-            .reduce(|x, y| x + y)
-            .unwrap_or_default();
-        candidates
+        let finite_candidates: Vec<_> = candidates
             .iter()
+            .filter(|(_, notional)| notional.value.is_finite())
+            .collect();
+        let total_notional: f64 = finite_candidates
+            .iter()
+            .map(|(_, notional)| notional.value)
+            .sum();
+        if total_notional == 0.0 || !total_notional.is_finite() {
+            return HashMap::default();
+        }
+        finite_candidates
+            .into_iter()
             .map(|(name, notional)| (name.clone(), (notional.value / total_notional).into()))
             .collect()
     }
@@ -50,6 +59,54 @@ mod test {
         assert_eq!(expected_scores, actual_sores);
     }
 
+    #[test]
+    fn rank_ignoring_non_finite() {
+        // Given
+        let candidates: HashMap<_, _> = [
+            ("A".into(), 1.0.into()),
+            ("B".into(), 3.0.into()),
+            ("C".into(), f64::NAN.into()),
+            ("D".into(), f64::INFINITY.into()),
+        ]
+        .into();
+        let expected_scores: HashMap<_, _> =
+            [("A".into(), 0.25.into()), ("B".into(), 0.75.into())].into();
+
+        // When
+        let actual_sores = NotionalRanker.rank(&candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_sores);
+    }
+
+    #[test]
+    fn rank_zero_total() {
+        // Given
+        let candidates: HashMap<_, _> =
+            [("A".into(), 1.0.into()), ("B".into(), (-1.0).into())].into();
+        let expected_scores = HashMap::default();
+
+        // When
+        let actual_sores = NotionalRanker.rank(&candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_sores);
+    }
+
+    #[test]
+    fn rank_overflowing_total() {
+        // Given
+        let candidates: HashMap<_, _> =
+            [("A".into(), f64::MAX.into()), ("B".into(), f64::MAX.into())].into();
+        let expected_scores = HashMap::default();
+
+        // When
+        let actual_sores = NotionalRanker.rank(&candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_sores);
+    }
+
     #[test]
     fn rank_empty() {
         // Given
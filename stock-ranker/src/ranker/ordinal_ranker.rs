@@ -0,0 +1,178 @@
+use super::FactorRanker;
+use super::Score;
+use super::ScoringCandidates;
+use super::Ticker;
+use crate::config::RankerKind;
+use crate::scoring_candidate::ScoringFactor;
+use std::collections::HashMap;
+
+/// Scores by position instead of magnitude, so that an outlier can't dominate the factor.
+///
+/// Among `n` candidates, the best one gets `n` points and the worst one gets 1, tied candidates
+/// sharing the average of their points. Each candidate is then scored by its share of all points.
+pub struct OrdinalRanker {
+    factor_type: ScoringFactor,
+    greatest_winning: bool,
+}
+
+impl OrdinalRanker {
+    pub fn new(factor_type: ScoringFactor, greatest_winning: bool) -> Self {
+        Self {
+            factor_type,
+            greatest_winning,
+        }
+    }
+}
+
+impl FactorRanker for OrdinalRanker {
+    fn rank(&self, candidates: &ScoringCandidates) -> HashMap<Ticker, Score> {
+        let mut values: Vec<_> = candidates
+            .iter()
+            .filter_map(|(ticker, factors)| {
+                factors
+                    .get(&self.factor_type)
+                    .filter(|notional| notional.value.is_finite())
+                    .map(|notional| (ticker.clone(), notional.value))
+            })
+            .collect();
+        values.sort_by(|(_, x), (_, y)| {
+            if self.greatest_winning {
+                x.total_cmp(y)
+            } else {
+                y.total_cmp(x)
+            }
+        });
+
+        let count = values.len();
+        let total_points = (count * (count + 1) / 2) as f64;
+        let mut scores = HashMap::default();
+        let mut start = 0;
+        while start < count {
+            let end = start
+                + values[start..]
+                    .iter()
+                    .take_while(|(_, value)| *value == values[start].1)
+                    .count();
+            // Positions are 0-based from the worst, so points are positions plus 1.
+            let points = (start + end + 1) as f64 / 2.0;
+            for (ticker, _) in values[start..end].iter() {
+                scores.insert(ticker.clone(), (points / total_points).into());
+            }
+            start = end;
+        }
+        scores
+    }
+    fn get_factor(&self) -> ScoringFactor {
+        self.factor_type
+    }
+    fn get_kind(&self) -> RankerKind {
+        if self.greatest_winning {
+            RankerKind::OrdinalGreatestWinning
+        } else {
+            RankerKind::OrdinalLeastWinning
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ranker::Notional;
+
+    fn candidates() -> ScoringCandidates {
+        [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(-0.4))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(-0.01))]),
+            ),
+            (
+                "C",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.02))]),
+            ),
+            (
+                "D",
+                HashMap::from([(ScoringFactor::LongTermTotalReturn, Notional::from(1.0))]),
+            ),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn rank_greatest_winning() {
+        // Given
+        let ranker = OrdinalRanker::new(ScoringFactor::OneMonthPriceChange, true);
+        let expected_scores: HashMap<_, _> = [
+            ("A".into(), (1.0 / 6.0).into()),
+            ("B".into(), (2.0 / 6.0).into()),
+            ("C".into(), (3.0 / 6.0).into()),
+        ]
+        .into();
+
+        // When
+        let actual_scores = ranker.rank(&candidates());
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+
+    #[test]
+    fn rank_least_winning() {
+        // Given
+        let ranker = OrdinalRanker::new(ScoringFactor::OneMonthPriceChange, false);
+        let expected_scores: HashMap<_, _> = [
+            ("A".into(), (3.0 / 6.0).into()),
+            ("B".into(), (2.0 / 6.0).into()),
+            ("C".into(), (1.0 / 6.0).into()),
+        ]
+        .into();
+
+        // When
+        let actual_scores = ranker.rank(&candidates());
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+
+    #[test]
+    fn rank_ties() {
+        // Given
+        let candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.1))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.1))]),
+            ),
+            (
+                "C",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.0))]),
+            ),
+        ]
+        .into();
+        let ranker = OrdinalRanker::new(ScoringFactor::OneMonthPriceChange, true);
+        let expected_scores: HashMap<_, _> = [
+            ("A".into(), (2.5 / 6.0).into()),
+            ("B".into(), (2.5 / 6.0).into()),
+            ("C".into(), (1.0 / 6.0).into()),
+        ]
+        .into();
+
+        // When
+        let actual_scores = ranker.rank(&candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+
+    #[test]
+    fn rank_no_candidate() {
+        let ranker = OrdinalRanker::new(ScoringFactor::OneMonthPriceChange, true);
+        assert!(ranker.rank(&ScoringCandidates::default()).is_empty());
+    }
+}
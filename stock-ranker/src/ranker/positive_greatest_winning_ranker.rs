@@ -2,6 +2,7 @@ use super::FactorRanker;
 use super::Score;
 use super::ScoringCandidates;
 use super::Ticker;
+use crate::config::RankerKind;
 use crate::scoring_candidate::ScoringFactor;
 use std::collections::HashMap;
 
@@ -39,6 +40,9 @@ impl FactorRanker for PositiveGreatestWinningRanker {
     fn get_factor(&self) -> ScoringFactor {
         self.factor_type
     }
+    fn get_kind(&self) -> RankerKind {
+        RankerKind::PositiveGreatestWinning
+    }
 }
 
 #[cfg(test)]
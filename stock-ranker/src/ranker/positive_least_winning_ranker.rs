@@ -0,0 +1,192 @@
+use super::FactorRanker;
+use super::Score;
+use super::ScoringCandidates;
+use super::Ticker;
+use crate::config::RankerKind;
+use crate::scoring_candidate::ScoringFactor;
+use std::collections::HashMap;
+
+#[mockall_double::double]
+use super::notional_ranker::NotionalRanker;
+
+/// Favors the smallest value of 0 or above by ranking the inverse of the value plus 1, so that lower
+/// risk wins without a value near 0 taking the whole factor.
+pub struct PositiveLeastWinningRanker {
+    notional_ranker: NotionalRanker,
+    factor_type: ScoringFactor,
+}
+
+impl PositiveLeastWinningRanker {
+    pub fn new(factor_type: ScoringFactor) -> Self {
+        Self {
+            notional_ranker: Default::default(),
+            factor_type,
+        }
+    }
+}
+
+impl FactorRanker for PositiveLeastWinningRanker {
+    fn rank(&self, candidates: &ScoringCandidates) -> HashMap<Ticker, Score> {
+        let notional_candidates: HashMap<_, _> = candidates
+            .iter()
+            .filter_map(|(ticker, factors)| {
+                factors
+                    .get(&self.factor_type)
+                    .filter(|notional| notional.value.is_finite() && notional.value >= 0.0)
+                    .map(|notional| (ticker.clone(), (1.0 / (1.0 + notional.value)).into()))
+            })
+            .collect();
+        self.notional_ranker.rank(&notional_candidates)
+    }
+    fn get_factor(&self) -> ScoringFactor {
+        self.factor_type
+    }
+    fn get_kind(&self) -> RankerKind {
+        RankerKind::PositiveLeastWinning
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ranker::Notional;
+
+    #[test]
+    fn rank_correct_candidates() {
+        // Given
+        let stock_candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::AnnualizedVolatility, Notional::from(0.25))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(1.0))]),
+            ),
+            (
+                "C",
+                HashMap::from([(ScoringFactor::AnnualizedVolatility, Notional::from(-1.0))]),
+            ),
+            (
+                "D",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.0))]),
+            ),
+            (
+                "E",
+                HashMap::from([(ScoringFactor::AnnualizedVolatility, Notional::from(0.0))]),
+            ),
+            (
+                "F",
+                HashMap::from([(ScoringFactor::AnnualizedVolatility, Notional::from(0.0001))]),
+            ),
+        ]
+        .into();
+        let expected_notional_candidates: HashMap<_, _> = [
+            ("A".into(), 0.8.into()),
+            ("E".into(), 1.0.into()),
+            ("F".into(), (1.0 / 1.0001).into()),
+        ]
+        .into();
+        let expected_scores = HashMap::default();
+        let mut notional_ranker = NotionalRanker::default();
+        notional_ranker
+            .expect_rank()
+            .withf_st(move |arg| arg == &expected_notional_candidates)
+            .return_const_st(expected_scores.clone());
+        let ranker = PositiveLeastWinningRanker {
+            notional_ranker,
+            factor_type: ScoringFactor::AnnualizedVolatility,
+        };
+
+        // When
+        let actual_scores = ranker.rank(&stock_candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+
+    #[test]
+    fn rank_single_candidate() {
+        // Given
+        let stock_candidates: ScoringCandidates = [(
+            "A",
+            HashMap::from([(ScoringFactor::MaxDrawdown, Notional::from(0.25))]),
+        )]
+        .into();
+        let expected_notional_candidates: HashMap<_, _> = [("A".into(), 0.8.into())].into();
+        let expected_scores: HashMap<_, _> = [("A".into(), 1.0.into())].into();
+        let mut notional_ranker = NotionalRanker::default();
+        notional_ranker
+            .expect_rank()
+            .withf_st(move |arg| arg == &expected_notional_candidates)
+            .return_const_st(expected_scores.clone());
+        let ranker = PositiveLeastWinningRanker {
+            notional_ranker,
+            factor_type: ScoringFactor::MaxDrawdown,
+        };
+
+        // When
+        let actual_scores = ranker.rank(&stock_candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+
+    #[test]
+    fn rank_same_values() {
+        // Given
+        let stock_candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::MaxDrawdown, Notional::from(0.25))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::MaxDrawdown, Notional::from(0.25))]),
+            ),
+        ]
+        .into();
+        let expected_notional_candidates: HashMap<_, _> =
+            [("A".into(), 0.8.into()), ("B".into(), 0.8.into())].into();
+        let expected_scores: HashMap<_, _> =
+            [("A".into(), 0.5.into()), ("B".into(), 0.5.into())].into();
+        let mut notional_ranker = NotionalRanker::default();
+        notional_ranker
+            .expect_rank()
+            .withf_st(move |arg| arg == &expected_notional_candidates)
+            .return_const_st(expected_scores.clone());
+        let ranker = PositiveLeastWinningRanker {
+            notional_ranker,
+            factor_type: ScoringFactor::MaxDrawdown,
+        };
+
+        // When
+        let actual_scores = ranker.rank(&stock_candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+
+    #[test]
+    fn rank_no_candidate() {
+        // Given
+        let stock_candidates = ScoringCandidates::default();
+        let expected_notional_candidates = HashMap::default();
+        let expected_scores = HashMap::default();
+        let mut notional_ranker = NotionalRanker::default();
+        notional_ranker
+            .expect_rank()
+            .withf_st(move |arg| arg == &expected_notional_candidates)
+            .return_const_st(expected_scores.clone());
+        let ranker = PositiveLeastWinningRanker {
+            notional_ranker,
+            factor_type: ScoringFactor::OneMonthPriceChange,
+        };
+
+        // When
+        let actual_scores = ranker.rank(&stock_candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+}
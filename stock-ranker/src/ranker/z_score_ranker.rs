@@ -0,0 +1,150 @@
+use super::FactorRanker;
+use super::Score;
+use super::ScoringCandidates;
+use super::Ticker;
+use crate::config::RankerKind;
+use crate::scoring_candidate::ScoringFactor;
+use std::collections::HashMap;
+
+#[mockall_double::double]
+use super::notional_ranker::NotionalRanker;
+
+/// Scores values of both signs by how many standard deviations they beat the average, ignoring the ones below it.
+pub struct ZScoreRanker {
+    notional_ranker: NotionalRanker,
+    factor_type: ScoringFactor,
+    greatest_winning: bool,
+}
+
+impl ZScoreRanker {
+    pub fn new(factor_type: ScoringFactor, greatest_winning: bool) -> Self {
+        Self {
+            notional_ranker: Default::default(),
+            factor_type,
+            greatest_winning,
+        }
+    }
+}
+
+impl FactorRanker for ZScoreRanker {
+    /// Returns no score at all if the values are all the same, since none beats another.
+    fn rank(&self, candidates: &ScoringCandidates) -> HashMap<Ticker, Score> {
+        let values: Vec<_> = candidates
+            .iter()
+            .filter_map(|(ticker, factors)| {
+                factors
+                    .get(&self.factor_type)
+                    .filter(|notional| notional.value.is_finite())
+                    .map(|notional| (ticker, notional.value))
+            })
+            .collect();
+        let count = values.len() as f64;
+        let mean = values.iter().map(|(_, value)| value).sum::<f64>() / count;
+        let variance = values
+            .iter()
+            .map(|(_, value)| (value - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        let deviation = variance.sqrt();
+        if deviation == 0.0 || !deviation.is_finite() {
+            return HashMap::default();
+        }
+        let notional_candidates: HashMap<_, _> = values
+            .into_iter()
+            .map(|(ticker, value)| {
+                let z_score = (value - mean) / deviation;
+                let z_score = if self.greatest_winning {
+                    z_score
+                } else {
+                    -z_score
+                };
+                (ticker.clone(), z_score.max(0.0).into())
+            })
+            .collect();
+        self.notional_ranker.rank(&notional_candidates)
+    }
+    fn get_factor(&self) -> ScoringFactor {
+        self.factor_type
+    }
+    fn get_kind(&self) -> RankerKind {
+        if self.greatest_winning {
+            RankerKind::ZScoreGreatestWinning
+        } else {
+            RankerKind::ZScoreLeastWinning
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ranker::Notional;
+    use test_case::case;
+
+    #[case(true, [0.0, 1.0])]
+    #[case(false, [1.0, 0.0])]
+    fn rank_correct_candidates(greatest_winning: bool, expected_notionals: [f64; 2]) {
+        // Given
+        let stock_candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(-0.1))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.3))]),
+            ),
+            (
+                "C",
+                HashMap::from([(ScoringFactor::LongTermTotalReturn, Notional::from(1.0))]),
+            ),
+        ]
+        .into();
+        let expected_notional_candidates: HashMap<_, _> = ["A", "B"]
+            .into_iter()
+            .zip(expected_notionals)
+            .map(|(ticker, notional)| (ticker.into(), notional.into()))
+            .collect();
+        let expected_scores: HashMap<_, _> = [("B".into(), 1.0.into())].into();
+        let mut notional_ranker = NotionalRanker::default();
+        notional_ranker
+            .expect_rank()
+            .withf_st(move |arg| arg == &expected_notional_candidates)
+            .return_const_st(expected_scores.clone());
+        let ranker = ZScoreRanker {
+            notional_ranker,
+            factor_type: ScoringFactor::OneMonthPriceChange,
+            greatest_winning,
+        };
+
+        // When
+        let actual_scores = ranker.rank(&stock_candidates);
+
+        // Then
+        assert_eq!(expected_scores, actual_scores);
+    }
+
+    #[test]
+    fn rank_same_values() {
+        // Given
+        let stock_candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.1))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.1))]),
+            ),
+        ]
+        .into();
+        let ranker = ZScoreRanker {
+            notional_ranker: NotionalRanker::default(),
+            factor_type: ScoringFactor::OneMonthPriceChange,
+            greatest_winning: true,
+        };
+
+        // Then
+        assert!(ranker.rank(&stock_candidates).is_empty());
+    }
+}
@@ -1,35 +1,46 @@
 use crate::arithmetic_renderer::ArithmeticRenderer;
+use crate::ordering::StockOrdering;
+use crate::ranker::FactorContribution;
 use crate::ranker::Notional;
-use crate::ranker::Score;
+use crate::ranker::ScoreBreakdown;
 use crate::ranker::Ticker;
 use crate::scoring_candidate::ScoringCandidates;
 use crate::scoring_candidate::ScoringFactor;
-use itertools::Itertools;
+use schema::FactorScore;
 use schema::StockReport;
 use std::collections::HashMap;
 
 #[derive(Default)]
 pub struct ReportRenderer {
     arithmetic_renderer: ArithmeticRenderer,
+    ordering: StockOrdering,
 }
 
 impl ReportRenderer {
+    pub fn new(ordering: StockOrdering) -> Self {
+        Self {
+            arithmetic_renderer: Default::default(),
+            ordering,
+        }
+    }
+
     pub fn render(
         &self,
         candidates: &ScoringCandidates,
-        scores: &HashMap<Ticker, Score>,
+        breakdowns: &HashMap<Ticker, ScoreBreakdown>,
     ) -> Vec<StockReport> {
-        candidates
+        // Tickers without a breakdown are excluded from the ranking.
+        let scores: HashMap<_, _> = breakdowns
             .iter()
-            .map(|(ticker, factors)| {
-                (
-                    ticker.to_string(),
-                    factors,
-                    scores.get(ticker).cloned().unwrap_or_default().value,
-                )
+            .map(|(ticker, breakdown)| (ticker.clone(), breakdown.total()))
+            .collect();
+        self.ordering
+            .sort(candidates, &scores)
+            .into_iter()
+            .filter_map(|(ticker, _)| {
+                let factors = candidates.get(&ticker)?;
+                Some(self.render_entry(ticker.to_string(), factors, &breakdowns[&ticker]))
             })
-            .sorted_unstable_by(|(_, _, x), (_, _, y)| y.total_cmp(x))
-            .map(|(ticker, factors, score)| self.render_entry(ticker, factors, score))
             .collect()
     }
 
@@ -37,28 +48,64 @@ impl ReportRenderer {
         self.arithmetic_renderer.render_float(score * 100.0)
     }
 
+    fn render_notional(&self, factor: ScoringFactor, notional: Notional) -> String {
+        if factor.is_percentage() {
+            self.arithmetic_renderer.render_percentage(notional.value)
+        } else {
+            self.arithmetic_renderer.render_float(notional.value)
+        }
+    }
+
+    fn render_contribution(&self, contribution: &FactorContribution) -> FactorScore {
+        FactorScore {
+            factor: contribution.factor.to_string(),
+            ranker: contribution.ranker.to_string(),
+            value: contribution
+                .notional
+                .map(|v| self.render_notional(contribution.factor, v)),
+            score: contribution
+                .score
+                .map(|v| self.arithmetic_renderer.render_percentage(v.value)),
+            weight: self.arithmetic_renderer.render_float(contribution.weight),
+            contribution: self.render_score(contribution.weighted_score().value),
+        }
+    }
+
     fn render_entry(
         &self,
         ticker: String,
         factors: &HashMap<ScoringFactor, Notional>,
-        score: f64,
+        breakdown: &ScoreBreakdown,
     ) -> StockReport {
-        let none = "None".to_string();
+        let render_factor = |factor| {
+            factors
+                .get(&factor)
+                .map(|v| self.render_notional(factor, *v))
+        };
         StockReport {
             ticker,
-            score: self.render_score(score),
-            one_month_price_change: factors
-                .get(&ScoringFactor::OneMonthPriceChange)
-                .map_or_else(
-                    || none.clone(),
-                    |v| self.arithmetic_renderer.render_percentage(v.value),
-                ),
-            long_term_total_return: factors
-                .get(&ScoringFactor::LongTermTotalReturn)
-                .map_or_else(
-                    || none.clone(),
-                    |v| self.arithmetic_renderer.render_percentage(v.value),
-                ),
+            score: self.render_score(breakdown.total().value),
+            one_month_price_change: render_factor(ScoringFactor::OneMonthPriceChange),
+            three_month_price_change: render_factor(ScoringFactor::ThreeMonthPriceChange),
+            six_month_price_change: render_factor(ScoringFactor::SixMonthPriceChange),
+            twelve_month_price_change: render_factor(ScoringFactor::TwelveMonthPriceChange),
+            one_year_total_return: render_factor(ScoringFactor::OneYearTotalReturn),
+            three_year_total_return: render_factor(ScoringFactor::ThreeYearTotalReturn),
+            long_term_total_return: render_factor(ScoringFactor::LongTermTotalReturn),
+            annualized_volatility: render_factor(ScoringFactor::AnnualizedVolatility),
+            max_drawdown: render_factor(ScoringFactor::MaxDrawdown),
+            trailing_pe: render_factor(ScoringFactor::TrailingPe),
+            forward_pe: render_factor(ScoringFactor::ForwardPe),
+            dividend_yield: render_factor(ScoringFactor::DividendYield),
+            eps_growth: render_factor(ScoringFactor::EpsGrowth),
+            expense_ratio: render_factor(ScoringFactor::ExpenseRatio),
+            assets_under_management: render_factor(ScoringFactor::AssetsUnderManagement),
+            tracking_difference: render_factor(ScoringFactor::TrackingDifference),
+            breakdown: breakdown
+                .contributions
+                .iter()
+                .map(|contribution| self.render_contribution(contribution))
+                .collect(),
         }
     }
 }
@@ -66,20 +113,35 @@ impl ReportRenderer {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::config::RankerKind;
+
+    fn breakdown(score: f64) -> ScoreBreakdown {
+        ScoreBreakdown {
+            contributions: vec![FactorContribution {
+                factor: ScoringFactor::OneMonthPriceChange,
+                ranker: RankerKind::NegativeLeastWinning,
+                notional: None,
+                score: Some(score.into()),
+                weight: 1.0,
+            }],
+        }
+    }
 
     #[test]
     fn entries_sorted_by_score_descendingly() {
         // Given
         let renderer = ReportRenderer {
             arithmetic_renderer: ArithmeticRenderer,
+            ordering: Default::default(),
         };
         let candidates: ScoringCandidates =
             [("A", Default::default()), ("B", Default::default())].into();
-        let scores: HashMap<_, _> = [("A".into(), 1.0.into()), ("B".into(), 2.0.into())].into();
+        let breakdowns: HashMap<_, _> =
+            [("A".into(), breakdown(1.0)), ("B".into(), breakdown(2.0))].into();
         let expected_tickers = vec!["B".to_string(), "A".to_string()];
 
         // When
-        let actual_report = renderer.render(&candidates, &scores);
+        let actual_report = renderer.render(&candidates, &breakdowns);
         let actual_tickers: Vec<_> = actual_report
             .into_iter()
             .map(|entry| entry.ticker)
@@ -88,4 +150,28 @@ mod test {
         // Then
         assert_eq!(expected_tickers, actual_tickers);
     }
+
+    #[test]
+    fn render_breakdown() {
+        // Given
+        let renderer = ReportRenderer::default();
+        let contribution = FactorContribution {
+            factor: ScoringFactor::LongTermTotalReturn,
+            ranker: RankerKind::PositiveGreatestWinning,
+            notional: Some(1.5.into()),
+            score: Some(0.25.into()),
+            weight: 4.0,
+        };
+
+        // When
+        let actual = renderer.render_contribution(&contribution);
+
+        // Then
+        assert_eq!("long_term_total_return", actual.factor);
+        assert_eq!("positive_greatest_winning", actual.ranker);
+        assert_eq!(Some("150%".to_string()), actual.value);
+        assert_eq!(Some("25%".to_string()), actual.score);
+        assert_eq!("4", actual.weight);
+        assert_eq!("100", actual.contribution);
+    }
 }
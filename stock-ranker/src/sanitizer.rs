@@ -0,0 +1,144 @@
+use crate::ranker::Notional;
+use crate::ranker::Ticker;
+use crate::scoring_candidate::ScoringCandidates;
+use crate::scoring_candidate::ScoringFactor;
+use derive_more::Display;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// Something suspicious in the factors, which may distort the ranking.
+#[derive(Debug, PartialEq, Display)]
+pub enum Diagnostic {
+    #[display("Excluded {factor} of {ticker} because {} is not a finite number", notional.value)]
+    NonFinite {
+        ticker: Ticker,
+        factor: ScoringFactor,
+        notional: Notional,
+    },
+
+    #[display("All {count} tickers have the same {factor} {value}, it cannot tell them apart")]
+    Degenerate {
+        factor: ScoringFactor,
+        value: f64,
+        count: usize,
+    },
+}
+
+/// Checks the factors before ranking, so that a single broken value can't poison every score.
+pub struct CandidateSanitizer;
+
+impl CandidateSanitizer {
+    /// Excludes non-finite factors and flags factors with no variation.
+    pub fn sanitize(&self, candidates: &mut ScoringCandidates) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::default();
+        candidates.retain(|ticker, factor, notional| {
+            let finite = notional.value.is_finite();
+            if !finite {
+                diagnostics.push(Diagnostic::NonFinite {
+                    ticker: ticker.clone(),
+                    factor,
+                    notional,
+                });
+            }
+            finite
+        });
+
+        let mut values_by_factor: HashMap<ScoringFactor, Vec<f64>> = HashMap::default();
+        for (_, factors) in candidates.iter() {
+            for (factor, notional) in factors.iter() {
+                values_by_factor
+                    .entry(*factor)
+                    .or_default()
+                    .push(notional.value);
+            }
+        }
+        for (factor, values) in values_by_factor {
+            if values.len() > 1 && values.iter().all(|value| *value == values[0]) {
+                diagnostics.push(Diagnostic::Degenerate {
+                    factor,
+                    value: values[0],
+                    count: values.len(),
+                });
+            }
+        }
+
+        diagnostics
+            .into_iter()
+            .sorted_by_cached_key(ToString::to_string)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exclude_non_finite() {
+        // Given
+        let mut candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([
+                    (ScoringFactor::OneMonthPriceChange, Notional::from(f64::NAN)),
+                    (ScoringFactor::LongTermTotalReturn, Notional::from(1.0)),
+                ]),
+            ),
+            (
+                "B",
+                HashMap::from([(
+                    ScoringFactor::OneMonthPriceChange,
+                    Notional::from(f64::INFINITY),
+                )]),
+            ),
+        ]
+        .into();
+
+        // When
+        let diagnostics = CandidateSanitizer.sanitize(&mut candidates);
+
+        // Then
+        assert_eq!(
+            vec![
+                "Excluded one_month_price_change of A because NaN is not a finite number",
+                "Excluded one_month_price_change of B because inf is not a finite number",
+            ],
+            diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+        let remaining: HashMap<_, _> = candidates
+            .iter()
+            .map(|(ticker, factors)| (ticker.to_string(), factors.len()))
+            .collect();
+        assert_eq!(HashMap::from([("A".into(), 1), ("B".into(), 0)]), remaining);
+    }
+
+    #[test]
+    fn flag_degenerate() {
+        // Given
+        let mut candidates: ScoringCandidates = [
+            (
+                "A",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.0))]),
+            ),
+            (
+                "B",
+                HashMap::from([(ScoringFactor::OneMonthPriceChange, Notional::from(0.0))]),
+            ),
+        ]
+        .into();
+        let expected = vec![Diagnostic::Degenerate {
+            factor: ScoringFactor::OneMonthPriceChange,
+            value: 0.0,
+            count: 2,
+        }];
+
+        // When
+        let diagnostics = CandidateSanitizer.sanitize(&mut candidates);
+
+        // Then
+        assert_eq!(expected, diagnostics);
+    }
+}
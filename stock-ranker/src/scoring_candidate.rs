@@ -1,11 +1,15 @@
 use crate::ranker::Notional;
 use crate::ranker::Ticker;
+use derive_more::Display;
+use schema::AssetType;
 use schema::ProductMetric;
+use serde::Deserialize;
 use std::collections::HashMap;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct ScoringCandidates {
     map: HashMap<Ticker, HashMap<ScoringFactor, Notional>>,
+    names: HashMap<Ticker, String>,
 }
 
 impl ScoringCandidates {
@@ -23,6 +27,39 @@ impl ScoringCandidates {
         }
     }
 
+    /// Registers a ticker even if it has no factor, so that it is still reported.
+    pub fn add_ticker(&mut self, ticker: Ticker) {
+        self.map.entry(ticker).or_default();
+    }
+
+    /// Keeps only the factors for which `predicate` returns `true`.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&Ticker, ScoringFactor, Notional) -> bool) {
+        for (ticker, factors) in self.map.iter_mut() {
+            factors.retain(|factor, notional| predicate(ticker, *factor, *notional));
+        }
+    }
+
+    /// Keeps only the tickers for which `predicate` returns `true`.
+    pub fn retain_tickers(
+        &mut self,
+        mut predicate: impl FnMut(&Ticker, &HashMap<ScoringFactor, Notional>) -> bool,
+    ) {
+        self.map
+            .retain(|ticker, factors| predicate(ticker, factors));
+    }
+
+    pub fn set_name(&mut self, ticker: Ticker, name: String) {
+        self.names.insert(ticker, name);
+    }
+
+    pub fn get_name(&self, ticker: &Ticker) -> Option<&str> {
+        self.names.get(ticker).map(String::as_str)
+    }
+
+    pub fn get(&self, ticker: &Ticker) -> Option<&HashMap<ScoringFactor, Notional>> {
+        self.map.get(ticker)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (&Ticker, &HashMap<ScoringFactor, Notional>)> {
         self.map.iter()
     }
@@ -36,7 +73,10 @@ impl<const N: usize> From<[(&'static str, HashMap<ScoringFactor, Notional>); N]>
             .into_iter()
             .map(|(ticker, factors)| (ticker.into(), factors))
             .collect();
-        Self { map }
+        Self {
+            map,
+            names: Default::default(),
+        }
     }
 }
 
@@ -47,24 +87,177 @@ impl ScoringCandidateExtractor {
         let mut candidates = ScoringCandidates::default();
         for stock in metrics {
             let ticker: Ticker = stock.ticker.as_str().into();
+            candidates.set_name(ticker.clone(), stock.name.clone());
+            candidates.add_ticker(ticker.clone());
 
-            candidates.add_candidate(
-                ticker.clone(),
-                ScoringFactor::OneMonthPriceChange,
-                stock.one_month_price_change.into(),
-            );
-            candidates.add_candidate(
-                ticker.clone(),
-                ScoringFactor::LongTermTotalReturn,
-                stock.long_term_total_return.into(),
-            );
+            for factor in ScoringFactor::ALL {
+                if !factor.applies_to(stock.asset_type.as_ref()) {
+                    continue;
+                }
+                if let Some(value) = factor.value_of(stock) {
+                    candidates.add_candidate(ticker.clone(), factor, value.into());
+                }
+            }
         }
         candidates
     }
 }
 
-#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
 pub enum ScoringFactor {
+    #[display("one_month_price_change")]
     OneMonthPriceChange,
+
+    #[display("three_month_price_change")]
+    ThreeMonthPriceChange,
+
+    #[display("six_month_price_change")]
+    SixMonthPriceChange,
+
+    #[display("twelve_month_price_change")]
+    TwelveMonthPriceChange,
+
+    #[display("one_year_total_return")]
+    OneYearTotalReturn,
+
+    #[display("three_year_total_return")]
+    ThreeYearTotalReturn,
+
+    #[display("long_term_total_return")]
     LongTermTotalReturn,
+
+    #[display("annualized_volatility")]
+    AnnualizedVolatility,
+
+    #[display("max_drawdown")]
+    MaxDrawdown,
+
+    #[display("trailing_pe")]
+    TrailingPe,
+
+    #[display("forward_pe")]
+    ForwardPe,
+
+    #[display("dividend_yield")]
+    DividendYield,
+
+    #[display("eps_growth")]
+    EpsGrowth,
+
+    #[display("expense_ratio")]
+    ExpenseRatio,
+
+    #[display("assets_under_management")]
+    AssetsUnderManagement,
+
+    #[display("tracking_difference")]
+    TrackingDifference,
+}
+
+impl ScoringFactor {
+    /// Every factor, in the order they are presented.
+    pub const ALL: [ScoringFactor; 16] = [
+        ScoringFactor::OneMonthPriceChange,
+        ScoringFactor::ThreeMonthPriceChange,
+        ScoringFactor::SixMonthPriceChange,
+        ScoringFactor::TwelveMonthPriceChange,
+        ScoringFactor::OneYearTotalReturn,
+        ScoringFactor::ThreeYearTotalReturn,
+        ScoringFactor::LongTermTotalReturn,
+        ScoringFactor::AnnualizedVolatility,
+        ScoringFactor::MaxDrawdown,
+        ScoringFactor::TrailingPe,
+        ScoringFactor::ForwardPe,
+        ScoringFactor::DividendYield,
+        ScoringFactor::EpsGrowth,
+        ScoringFactor::ExpenseRatio,
+        ScoringFactor::AssetsUnderManagement,
+        ScoringFactor::TrackingDifference,
+    ];
+
+    /// Reads the factor from the metrics of a product, absent if the collector couldn't find it.
+    pub fn value_of(&self, metric: &ProductMetric) -> Option<f64> {
+        match self {
+            ScoringFactor::OneMonthPriceChange => metric.one_month_price_change,
+            ScoringFactor::ThreeMonthPriceChange => metric.three_month_price_change,
+            ScoringFactor::SixMonthPriceChange => metric.six_month_price_change,
+            ScoringFactor::TwelveMonthPriceChange => metric.twelve_month_price_change,
+            ScoringFactor::OneYearTotalReturn => metric.one_year_total_return,
+            ScoringFactor::ThreeYearTotalReturn => metric.three_year_total_return,
+            ScoringFactor::LongTermTotalReturn => metric.long_term_total_return,
+            ScoringFactor::AnnualizedVolatility => metric.annualized_volatility,
+            ScoringFactor::MaxDrawdown => metric.max_drawdown,
+            ScoringFactor::TrailingPe => metric.trailing_pe,
+            ScoringFactor::ForwardPe => metric.forward_pe,
+            ScoringFactor::DividendYield => metric.dividend_yield,
+            ScoringFactor::EpsGrowth => metric.eps_growth,
+            ScoringFactor::ExpenseRatio => metric.expense_ratio,
+            ScoringFactor::AssetsUnderManagement => metric.assets_under_management,
+            ScoringFactor::TrackingDifference => metric.tracking_difference,
+        }
+    }
+
+    /// Whether the factor makes sense for a product of the type, which is unknown if absent.
+    pub fn applies_to(&self, asset_type: Option<&AssetType>) -> bool {
+        match self {
+            ScoringFactor::TrailingPe
+            | ScoringFactor::ForwardPe
+            | ScoringFactor::DividendYield
+            | ScoringFactor::EpsGrowth => matches!(asset_type, Some(AssetType::Stock)),
+            ScoringFactor::ExpenseRatio
+            | ScoringFactor::AssetsUnderManagement
+            | ScoringFactor::TrackingDifference => {
+                matches!(asset_type, Some(AssetType::ExchangeTradedFund))
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the factor is a ratio presented as a percentage, or otherwise a plain number.
+    pub fn is_percentage(&self) -> bool {
+        !matches!(
+            self,
+            ScoringFactor::TrailingPe
+                | ScoringFactor::ForwardPe
+                | ScoringFactor::AssetsUnderManagement
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extract_factors_by_asset_type() {
+        // Given
+        let metrics: Vec<ProductMetric> = serde_json::from_str(
+            r#"[
+                { "ticker": "A", "name": "A", "asset_type": "Stock", "trailing_pe": 20 },
+                { "ticker": "B", "name": "B", "asset_type": "Exchange-Traded Fund", "trailing_pe": 20, "expense_ratio": 0.001 },
+                { "ticker": "C", "name": "C", "trailing_pe": 20, "expense_ratio": 0.001, "one_month_price_change": 0.1 }
+            ]"#,
+        )
+        .unwrap();
+
+        // When
+        let candidates = ScoringCandidateExtractor.extract_scoring_candidates(&metrics);
+
+        // Then
+        assert_eq!(3, candidates.iter().count());
+        let factors_of = |ticker: &str| {
+            let mut factors: Vec<_> = candidates
+                .get(&ticker.into())
+                .into_iter()
+                .flat_map(HashMap::keys)
+                .copied()
+                .collect();
+            factors.sort_by_key(ToString::to_string);
+            factors
+        };
+        assert_eq!(vec![ScoringFactor::TrailingPe], factors_of("A"));
+        assert_eq!(vec![ScoringFactor::ExpenseRatio], factors_of("B"));
+        assert_eq!(vec![ScoringFactor::OneMonthPriceChange], factors_of("C"));
+    }
 }
@@ -0,0 +1,371 @@
+use crate::cli::Cli;
+use crate::cli::OutputFormat;
+use clap::ValueEnum;
+use derive_more::Display;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const SKIP_COUNT_VARIABLE: &str = "STOCK_RANKER_SKIP_COUNT";
+const INVEST_COUNT_VARIABLE: &str = "STOCK_RANKER_INVEST_COUNT";
+const OUTPUT_FORMAT_VARIABLE: &str = "STOCK_RANKER_OUTPUT_FORMAT";
+const TABLE_BORDER_VARIABLE: &str = "STOCK_RANKER_TABLE_BORDER";
+const CONFIG_VARIABLE: &str = "STOCK_RANKER_CONFIG";
+const STRICT_VARIABLE: &str = "STOCK_RANKER_STRICT";
+const CASH_VARIABLE: &str = "STOCK_RANKER_CASH";
+const FRACTIONAL_SHARES_VARIABLE: &str = "STOCK_RANKER_FRACTIONAL_SHARES";
+
+/// Settings of a run, validated and tagged with where each of them comes from.
+#[derive(Debug, PartialEq)]
+pub struct Settings {
+    pub skip_count: Setting<usize>,
+
+    /// Absent when neither the command line nor the environment specifies it.
+    pub invest_count: Option<Setting<usize>>,
+
+    pub format: Setting<OutputFormat>,
+    pub border: Setting<bool>,
+
+    /// Absent when neither the command line nor the environment specifies it.
+    pub config: Option<Setting<PathBuf>>,
+
+    /// Whether any issue in the input aborts the run.
+    pub strict: Setting<bool>,
+
+    /// Absent when neither the command line nor the environment specifies it.
+    pub cash: Option<Setting<f64>>,
+
+    pub fractional_shares: Setting<bool>,
+}
+
+impl Settings {
+    /// Resolves how many stocks to invest in, making sure the investment window fits in the ranking.
+    pub fn resolve_invest_count(&self, ticker_count: usize) -> anyhow::Result<usize> {
+        let invest_count = self.invest_count.unwrap_or(Setting {
+            value: (ticker_count / 2).max(1),
+            source: SettingSource::Default,
+        });
+        if invest_count.value == 0 {
+            anyhow::bail!(
+                "Invest count from {} is 0, no stock would be invested in",
+                invest_count.source
+            )
+        }
+        if self.skip_count.value + invest_count.value > ticker_count {
+            anyhow::bail!(
+                "Skip count {} (from {}) plus invest count {} (from {}) exceeds the number of ranked tickers {}",
+                self.skip_count.value,
+                self.skip_count.source,
+                invest_count.value,
+                invest_count.source,
+                ticker_count
+            )
+        }
+        Ok(invest_count.value)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: SettingSource,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Display)]
+pub enum SettingSource {
+    #[display("command-line option --{_0}")]
+    CommandLine(&'static str),
+
+    #[display("environment variable {_0}")]
+    Environment(&'static str),
+
+    #[display("default value")]
+    Default,
+}
+
+/// Merges the command line with environment variables, the former taking precedence.
+pub struct SettingsLoader {
+    environment: HashMap<String, String>,
+}
+
+impl SettingsLoader {
+    pub fn new(environment: HashMap<String, String>) -> Self {
+        Self { environment }
+    }
+
+    pub fn from_env() -> Self {
+        let environment = std::env::vars_os()
+            .map(|(key, value)| {
+                (
+                    key.to_string_lossy().into_owned(),
+                    value.to_string_lossy().into_owned(),
+                )
+            })
+            .filter(|(key, _)| key.starts_with("STOCK_RANKER_"))
+            .collect();
+        Self::new(environment)
+    }
+
+    /// Loads all settings, reporting every invalid one instead of falling back to a default.
+    pub fn load(&self, cli: &Cli) -> anyhow::Result<Settings> {
+        let mut errors = Vec::default();
+        let skip_count = self
+            .resolve(
+                cli.skip_count,
+                "skip-count",
+                SKIP_COUNT_VARIABLE,
+                usize::from_str,
+                &mut errors,
+            )
+            .unwrap_or(Setting {
+                value: 0,
+                source: SettingSource::Default,
+            });
+        let invest_count = self.resolve(
+            cli.invest_count,
+            "invest-count",
+            INVEST_COUNT_VARIABLE,
+            usize::from_str,
+            &mut errors,
+        );
+        let format = self
+            .resolve(
+                cli.format,
+                "format",
+                OUTPUT_FORMAT_VARIABLE,
+                |text| OutputFormat::from_str(text, true),
+                &mut errors,
+            )
+            .unwrap_or(Setting {
+                value: Default::default(),
+                source: SettingSource::Default,
+            });
+        let border = self
+            .resolve(
+                cli.border.then_some(true),
+                "border",
+                TABLE_BORDER_VARIABLE,
+                bool::from_str,
+                &mut errors,
+            )
+            .unwrap_or(Setting {
+                value: false,
+                source: SettingSource::Default,
+            });
+        let config = self.resolve(
+            cli.config.clone(),
+            "config",
+            CONFIG_VARIABLE,
+            PathBuf::from_str,
+            &mut errors,
+        );
+        let strict = self
+            .resolve(
+                cli.strict.then_some(true),
+                "strict",
+                STRICT_VARIABLE,
+                bool::from_str,
+                &mut errors,
+            )
+            .unwrap_or(Setting {
+                value: false,
+                source: SettingSource::Default,
+            });
+        let cash = self.resolve(cli.cash, "cash", CASH_VARIABLE, f64::from_str, &mut errors);
+        if let Some(cash) = cash
+            && !(cash.value.is_finite() && cash.value >= 0.0)
+        {
+            errors.push(format!(
+                "Invalid value `{}` from {}: not a non-negative amount",
+                cash.value, cash.source
+            ));
+        }
+        let fractional_shares = self
+            .resolve(
+                cli.fractional_shares.then_some(true),
+                "fractional-shares",
+                FRACTIONAL_SHARES_VARIABLE,
+                bool::from_str,
+                &mut errors,
+            )
+            .unwrap_or(Setting {
+                value: false,
+                source: SettingSource::Default,
+            });
+
+        if !errors.is_empty() {
+            anyhow::bail!("Invalid settings:\n{}", errors.join("\n"))
+        }
+        Ok(Settings {
+            skip_count,
+            invest_count,
+            format,
+            border,
+            config,
+            strict,
+            cash,
+            fractional_shares,
+        })
+    }
+
+    fn resolve<T, E: Display>(
+        &self,
+        cli_value: Option<T>,
+        option: &'static str,
+        variable: &'static str,
+        parse: impl Fn(&str) -> Result<T, E>,
+        errors: &mut Vec<String>,
+    ) -> Option<Setting<T>> {
+        if let Some(value) = cli_value {
+            return Some(Setting {
+                value,
+                source: SettingSource::CommandLine(option),
+            });
+        }
+        let text = self.environment.get(variable)?;
+        match parse(text) {
+            Ok(value) => Some(Setting {
+                value,
+                source: SettingSource::Environment(variable),
+            }),
+            Err(e) => {
+                errors.push(format!(
+                    "Invalid value `{}` from {}: {}",
+                    text,
+                    SettingSource::Environment(variable),
+                    e
+                ));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    fn load(args: &[&str], environment: &[(&str, &str)]) -> anyhow::Result<Settings> {
+        let cli = Cli::parse_from([&["stock-ranker"][..], args].concat());
+        let environment = environment
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        SettingsLoader::new(environment).load(&cli)
+    }
+
+    #[test]
+    fn load_defaults() {
+        // When
+        let settings = load(&[], &[]).unwrap();
+
+        // Then
+        let expected = Settings {
+            skip_count: Setting {
+                value: 0,
+                source: SettingSource::Default,
+            },
+            invest_count: None,
+            format: Setting {
+                value: OutputFormat::Table,
+                source: SettingSource::Default,
+            },
+            border: Setting {
+                value: false,
+                source: SettingSource::Default,
+            },
+            config: None,
+            strict: Setting {
+                value: false,
+                source: SettingSource::Default,
+            },
+            cash: None,
+            fractional_shares: Setting {
+                value: false,
+                source: SettingSource::Default,
+            },
+        };
+        assert_eq!(expected, settings);
+    }
+
+    #[test]
+    fn command_line_overrides_environment() {
+        // When
+        let settings = load(
+            &["--invest-count", "3"],
+            &[(INVEST_COUNT_VARIABLE, "5"), (SKIP_COUNT_VARIABLE, "1")],
+        )
+        .unwrap();
+
+        // Then
+        let expected_invest_count = Setting {
+            value: 3,
+            source: SettingSource::CommandLine("invest-count"),
+        };
+        let expected_skip_count = Setting {
+            value: 1,
+            source: SettingSource::Environment(SKIP_COUNT_VARIABLE),
+        };
+        assert_eq!(Some(expected_invest_count), settings.invest_count);
+        assert_eq!(expected_skip_count, settings.skip_count);
+    }
+
+    #[test]
+    fn load_format_from_environment() {
+        let settings = load(&[], &[(OUTPUT_FORMAT_VARIABLE, "json")]).unwrap();
+        assert_eq!(OutputFormat::Json, settings.format.value);
+    }
+
+    #[test]
+    fn reject_malformed_environment() {
+        // When
+        let error = load(
+            &[],
+            &[
+                (INVEST_COUNT_VARIABLE, "5x"),
+                (TABLE_BORDER_VARIABLE, "yes"),
+            ],
+        )
+        .unwrap_err()
+        .to_string();
+
+        // Then
+        assert!(error.contains("`5x` from environment variable STOCK_RANKER_INVEST_COUNT"));
+        assert!(error.contains("`yes` from environment variable STOCK_RANKER_TABLE_BORDER"));
+    }
+
+    #[test]
+    fn reject_negative_cash() {
+        let error = load(&[], &[(CASH_VARIABLE, "-100")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("`-100` from environment variable STOCK_RANKER_CASH"));
+    }
+
+    #[test]
+    fn resolve_default_invest_count() {
+        let settings = load(&[], &[]).unwrap();
+        assert_eq!(2, settings.resolve_invest_count(5).unwrap());
+        assert_eq!(1, settings.resolve_invest_count(1).unwrap());
+    }
+
+    #[test]
+    fn reject_window_exceeding_tickers() {
+        // Given
+        let settings = load(&["--skip-count", "2"], &[(INVEST_COUNT_VARIABLE, "2")]).unwrap();
+
+        // When
+        let error = settings.resolve_invest_count(3).unwrap_err().to_string();
+
+        // Then
+        assert!(error.contains("exceeds the number of ranked tickers 3"));
+    }
+
+    #[test]
+    fn reject_zero_invest_count() {
+        let settings = load(&["--invest-count", "0"], &[]).unwrap();
+        assert!(settings.resolve_invest_count(3).is_err());
+    }
+}
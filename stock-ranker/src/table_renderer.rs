@@ -0,0 +1,403 @@
+use schema::StockAdvice;
+use schema::StockReport;
+use schema::Trade;
+use schema::TradeAction;
+use unicode_width::UnicodeWidthStr;
+
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>, rows: Vec<Vec<String>>) -> Self {
+        Self { columns, rows }
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+}
+
+/// Reads a factor column from a report entry.
+type FactorField = fn(&StockReport) -> &Option<String>;
+
+impl From<&[StockReport]> for Table {
+    /// Leaves out the factors that no entry has.
+    fn from(report: &[StockReport]) -> Self {
+        let factor_columns: [(&str, FactorField); 16] = [
+            ("單月漲幅", |entry| &entry.one_month_price_change),
+            ("三月漲幅", |entry| &entry.three_month_price_change),
+            ("六月漲幅", |entry| &entry.six_month_price_change),
+            ("十二月漲幅", |entry| &entry.twelve_month_price_change),
+            ("一年回報", |entry| &entry.one_year_total_return),
+            ("三年回報", |entry| &entry.three_year_total_return),
+            ("長期回報", |entry| &entry.long_term_total_return),
+            ("波動率", |entry| &entry.annualized_volatility),
+            ("最大回撤", |entry| &entry.max_drawdown),
+            ("本益比", |entry| &entry.trailing_pe),
+            ("預估本益比", |entry| &entry.forward_pe),
+            ("殖利率", |entry| &entry.dividend_yield),
+            ("EPS成長", |entry| &entry.eps_growth),
+            ("總費用率", |entry| &entry.expense_ratio),
+            ("資產規模", |entry| &entry.assets_under_management),
+            ("追蹤差異", |entry| &entry.tracking_difference),
+        ];
+        let factor_columns: Vec<_> = factor_columns
+            .into_iter()
+            .filter(|(_, field)| report.iter().any(|entry| field(entry).is_some()))
+            .collect();
+        let columns = [
+            Column::new("ticker", Alignment::Left),
+            Column::new("得分", Alignment::Right),
+        ]
+        .into_iter()
+        .chain(
+            factor_columns
+                .iter()
+                .map(|(header, _)| Column::new(*header, Alignment::Right)),
+        )
+        .collect();
+        let rows = report
+            .iter()
+            .map(|entry| {
+                [entry.ticker.clone(), entry.score.clone()]
+                    .into_iter()
+                    .chain(
+                        factor_columns
+                            .iter()
+                            .map(|(_, field)| field(entry).clone().unwrap_or_default()),
+                    )
+                    .collect()
+            })
+            .collect();
+        Self::new(columns, rows)
+    }
+}
+
+impl From<&[StockAdvice]> for Table {
+    fn from(advice: &[StockAdvice]) -> Self {
+        let mut columns = vec![
+            Column::new("ticker", Alignment::Left),
+            Column::new("ratio", Alignment::Right),
+        ];
+        // Amounts and quantities are only shown if the investable cash is given.
+        let has_amount = advice.iter().any(|entry| entry.amount.is_some());
+        if has_amount {
+            columns.push(Column::new("effective_ratio", Alignment::Right));
+            columns.push(Column::new("amount", Alignment::Right));
+            columns.push(Column::new("quantity", Alignment::Right));
+        }
+        let rows = advice
+            .iter()
+            .map(|entry| {
+                let mut row = vec![entry.ticker.clone(), entry.ratio.clone()];
+                if has_amount {
+                    row.push(entry.effective_ratio.clone().unwrap_or_default());
+                    row.push(entry.amount.clone().unwrap_or_default());
+                    row.push(entry.quantity.clone().unwrap_or_default());
+                }
+                row
+            })
+            .collect();
+        Self::new(columns, rows)
+    }
+}
+
+impl From<&[Trade]> for Table {
+    fn from(trades: &[Trade]) -> Self {
+        let mut columns = vec![
+            Column::new("ticker", Alignment::Left),
+            Column::new("action", Alignment::Left),
+            Column::new("quantity", Alignment::Right),
+            Column::new("amount", Alignment::Right),
+            Column::new("current_ratio", Alignment::Right),
+            Column::new("target_ratio", Alignment::Right),
+            Column::new("effective_ratio", Alignment::Right),
+        ];
+        // Realized gains are only shown if any sale has a known cost basis.
+        let has_gain = trades.iter().any(|trade| trade.realized_gain.is_some());
+        if has_gain {
+            columns.push(Column::new("realized_gain", Alignment::Right));
+        }
+        let rows = trades
+            .iter()
+            .map(|trade| {
+                let action = match trade.action {
+                    TradeAction::Buy => "Buy",
+                    TradeAction::Sell => "Sell",
+                    TradeAction::Hold => "Hold",
+                };
+                let mut row = vec![
+                    trade.ticker.clone(),
+                    action.to_string(),
+                    trade.quantity.clone(),
+                    trade.amount.clone(),
+                    trade.current_ratio.clone(),
+                    trade.target_ratio.clone(),
+                    trade.effective_ratio.clone(),
+                ];
+                if has_gain {
+                    row.push(trade.realized_gain.clone().unwrap_or_default());
+                }
+                row
+            })
+            .collect();
+        Self::new(columns, rows)
+    }
+}
+
+pub struct Column {
+    header: String,
+    alignment: Alignment,
+}
+
+impl Column {
+    pub fn new(header: impl Into<String>, alignment: Alignment) -> Self {
+        Self {
+            header: header.into(),
+            alignment,
+        }
+    }
+
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    pub fn alignment(&self) -> Alignment {
+        self.alignment
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Alignment {
+    Left,
+    Right,
+}
+
+/// Renders a [Table] as plain text, measuring cells by their display width so that CJK text lines up.
+#[derive(Default)]
+pub struct TableRenderer {
+    border: bool,
+}
+
+impl TableRenderer {
+    pub fn new(border: bool) -> Self {
+        Self { border }
+    }
+
+    pub fn render(&self, table: &Table) -> String {
+        let widths = self.column_widths(table);
+        let header: Vec<_> = table
+            .columns
+            .iter()
+            .map(|column| column.header.as_str())
+            .collect();
+        let mut lines = Vec::with_capacity(table.rows.len() + 4);
+        if self.border {
+            lines.push(self.render_separator(&widths, '┌', '┬', '┐'));
+            lines.push(self.render_row(table, &widths, &header));
+            lines.push(self.render_separator(&widths, '├', '┼', '┤'));
+        } else {
+            lines.push(self.render_row(table, &widths, &header));
+            let underline: Vec<_> = table
+                .columns
+                .iter()
+                .map(|column| "-".repeat(column.header.width()))
+                .collect();
+            lines.push(self.render_row(table, &widths, &underline));
+        }
+        for row in &table.rows {
+            lines.push(self.render_row(table, &widths, row));
+        }
+        if self.border {
+            lines.push(self.render_separator(&widths, '└', '┴', '┘'));
+        }
+        lines
+            .into_iter()
+            .map(|line| format!("{}\n", line))
+            .collect()
+    }
+
+    fn column_widths(&self, table: &Table) -> Vec<usize> {
+        table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                table
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .map(|cell| cell.width())
+                    .chain([column.header.width()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    fn render_row(&self, table: &Table, widths: &[usize], cells: &[impl AsRef<str>]) -> String {
+        let padded: Vec<_> = table
+            .columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(index, (column, width))| {
+                let cell = cells.get(index).map_or("", AsRef::as_ref);
+                self.pad(cell, *width, column.alignment)
+            })
+            .collect();
+        if self.border {
+            format!("│ {} │", padded.join(" │ "))
+        } else {
+            padded.join(" ").trim_end().into()
+        }
+    }
+
+    fn render_separator(&self, widths: &[usize], left: char, middle: char, right: char) -> String {
+        let segments: Vec<_> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+        format!("{}{}{}", left, segments.join(&middle.to_string()), right)
+    }
+
+    fn pad(&self, cell: &str, width: usize, alignment: Alignment) -> String {
+        let padding = " ".repeat(width.saturating_sub(cell.width()));
+        match alignment {
+            Alignment::Left => format!("{}{}", cell, padding),
+            Alignment::Right => format!("{}{}", padding, cell),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample_table() -> Table {
+        Table::new(
+            vec![
+                Column::new("ticker", Alignment::Left),
+                Column::new("得分", Alignment::Right),
+            ],
+            vec![
+                vec!["A".into(), "1.5".into()],
+                vec!["LONGER".into(), "10".into()],
+            ],
+        )
+    }
+
+    #[test]
+    fn render_without_border() {
+        // Given
+        let expected = "\
+ticker 得分
+------ ----
+A       1.5
+LONGER   10
+";
+
+        // When
+        let actual = TableRenderer::new(false).render(&sample_table());
+
+        // Then
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn render_with_border() {
+        // Given
+        let expected = "\
+┌────────┬──────┐
+│ ticker │ 得分 │
+├────────┼──────┤
+│ A      │  1.5 │
+│ LONGER │   10 │
+└────────┴──────┘
+";
+
+        // When
+        let actual = TableRenderer::new(true).render(&sample_table());
+
+        // Then
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn from_report_without_missing_factors() {
+        // Given
+        let report = [StockReport {
+            ticker: "A".into(),
+            score: "100".into(),
+            one_month_price_change: Some("-5%".into()),
+            three_month_price_change: None,
+            six_month_price_change: None,
+            twelve_month_price_change: None,
+            one_year_total_return: Some("20%".into()),
+            three_year_total_return: None,
+            long_term_total_return: None,
+            annualized_volatility: Some("15%".into()),
+            max_drawdown: None,
+            trailing_pe: None,
+            forward_pe: None,
+            dividend_yield: None,
+            eps_growth: None,
+            expense_ratio: None,
+            assets_under_management: None,
+            tracking_difference: None,
+            breakdown: Default::default(),
+        }];
+
+        // When
+        let table = Table::from(&report[..]);
+
+        // Then
+        let headers: Vec<_> = table.columns().iter().map(Column::header).collect();
+        assert_eq!(
+            vec!["ticker", "得分", "單月漲幅", "一年回報", "波動率"],
+            headers
+        );
+        assert_eq!(vec![vec!["A", "100", "-5%", "20%", "15%"]], table.rows());
+    }
+
+    #[test]
+    fn from_advice_with_amount() {
+        // Given
+        let advice = [
+            StockAdvice {
+                ticker: "A".into(),
+                ratio: "60%".into(),
+                effective_ratio: Some("60%".into()),
+                amount: Some("600".into()),
+                quantity: Some("4".into()),
+            },
+            StockAdvice {
+                ticker: "B".into(),
+                ratio: "40%".into(),
+                effective_ratio: Some("40%".into()),
+                amount: Some("400".into()),
+                quantity: None,
+            },
+        ];
+
+        // When
+        let table = Table::from(&advice[..]);
+
+        // Then
+        let headers: Vec<_> = table.columns().iter().map(Column::header).collect();
+        assert_eq!(
+            vec!["ticker", "ratio", "effective_ratio", "amount", "quantity"],
+            headers
+        );
+        assert_eq!(
+            vec![
+                vec!["A", "60%", "60%", "600", "4"],
+                vec!["B", "40%", "40%", "400", ""]
+            ],
+            table.rows()
+        );
+    }
+}
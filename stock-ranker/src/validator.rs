@@ -0,0 +1,408 @@
+use crate::scoring_candidate::ScoringFactor;
+use derive_more::Display;
+use schema::ProductMetric;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+/// Price changes beyond this ratio are most likely a scrapping error.
+const MAX_PLAUSIBLE_PRICE_CHANGE: f64 = 10.0;
+
+/// Total returns beyond this ratio are most likely a scrapping error.
+const MAX_PLAUSIBLE_TOTAL_RETURN: f64 = 100.0;
+
+/// Annualized volatility beyond this ratio is most likely a scrapping error.
+const MAX_PLAUSIBLE_VOLATILITY: f64 = 5.0;
+
+/// Price-to-earnings ratios beyond this are most likely a scrapping error.
+const MAX_PLAUSIBLE_PE: f64 = 1000.0;
+
+/// Dividend yields beyond this ratio are most likely a scrapping error.
+const MAX_PLAUSIBLE_DIVIDEND_YIELD: f64 = 0.5;
+
+/// Expense ratios beyond this are most likely a scrapping error.
+const MAX_PLAUSIBLE_EXPENSE_RATIO: f64 = 0.05;
+
+/// Funds outperforming their index beyond this ratio are most likely a scrapping error.
+const MAX_PLAUSIBLE_TRACKING_DIFFERENCE: f64 = 0.2;
+
+/// Metrics collected more days ago than this are likely stale.
+const MAX_METRIC_AGE_DAYS: i64 = 7;
+
+/// Something wrong with an entry of the input.
+#[derive(Debug, PartialEq, Display)]
+#[display("{severity}: [{index}].{field} ({ticker:?}): {message}")]
+pub struct Issue {
+    /// Position of the entry in the input.
+    pub index: usize,
+
+    pub ticker: String,
+    pub field: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Display)]
+pub enum Severity {
+    /// The entry can't be ranked.
+    #[display("error")]
+    Error,
+
+    /// The entry can be ranked but probably shouldn't.
+    #[display("warning")]
+    Warning,
+}
+
+/// Checks the stock metrics for mistakes that would otherwise go unnoticed.
+pub struct MetricValidator;
+
+impl MetricValidator {
+    pub fn validate(&self, metrics: &[ProductMetric]) -> Vec<Issue> {
+        let mut issues = Vec::default();
+        let today = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() / 86400) as i64;
+        let mut first_indexes: HashMap<&str, usize> = HashMap::default();
+        for (index, metric) in metrics.iter().enumerate() {
+            let mut report = |field: &str, severity, message: String| {
+                issues.push(Issue {
+                    index,
+                    ticker: metric.ticker.clone(),
+                    field: field.into(),
+                    severity,
+                    message,
+                })
+            };
+            if metric.ticker.trim().is_empty() {
+                report("ticker", Severity::Error, "Ticker is empty".into());
+            } else if let Some(first_index) = first_indexes.get(metric.ticker.as_str()) {
+                report(
+                    "ticker",
+                    Severity::Error,
+                    format!("Duplicate of entry [{}]", first_index),
+                );
+            } else {
+                first_indexes.insert(&metric.ticker, index);
+            }
+            if metric.name.trim().is_empty() {
+                report("name", Severity::Warning, "Name is empty".into());
+            }
+            if let Some(currency) = &metric.currency
+                && !self.is_currency_code(currency)
+            {
+                report(
+                    "currency",
+                    Severity::Warning,
+                    format!("{:?} is not an ISO 4217 code like \"USD\"", currency),
+                );
+            }
+            if let Some(as_of) = &metric.as_of
+                && let Some(message) = self.check_as_of(as_of, today)
+            {
+                report("as_of", Severity::Warning, message);
+            }
+            if let Some(price) = metric.price
+                && !(price.is_finite() && price > 0.0)
+            {
+                report(
+                    "price",
+                    Severity::Warning,
+                    format!("{} is not a positive price, so no share is advised", price),
+                );
+            }
+            for factor in ScoringFactor::ALL {
+                let Some(value) = factor.value_of(metric) else {
+                    continue;
+                };
+                if !factor.applies_to(metric.asset_type.as_ref()) {
+                    report(
+                        &factor.to_string(),
+                        Severity::Warning,
+                        "Ignored because it doesn't apply to this type of product".into(),
+                    );
+                    continue;
+                }
+                let (min, max) = self.bounds(factor);
+                if let Some((severity, message)) = self.check_value(value, min, max) {
+                    report(&factor.to_string(), severity, message);
+                }
+            }
+        }
+        issues
+    }
+
+    fn is_currency_code(&self, text: &str) -> bool {
+        text.len() == 3 && text.chars().all(|c| c.is_ascii_uppercase())
+    }
+
+    /// Warns if the metrics are older than [MAX_METRIC_AGE_DAYS] as of `today`, in days since
+    /// 1970-01-01.
+    fn check_as_of(&self, as_of: &str, today: i64) -> Option<String> {
+        let Some(day) = self.parse_day(as_of) else {
+            return Some(format!("{:?} is not an RFC 3339 date-time", as_of));
+        };
+        let age = today - day;
+        (age > MAX_METRIC_AGE_DAYS).then(|| {
+            format!(
+                "Collected {} days ago, so the metrics are likely stale",
+                age
+            )
+        })
+    }
+
+    /// Days since 1970-01-01 of the date of an RFC 3339 date-time, ignoring the time and its
+    /// offset since they shift the date by less than a day.
+    fn parse_day(&self, date_time: &str) -> Option<i64> {
+        let (date, time) = date_time.split_at_checked(10)?;
+        if !time.starts_with(['T', 't', ' ']) {
+            return None;
+        }
+        let mut parts = date.split('-');
+        let mut next = |len: usize| {
+            parts
+                .next()
+                .filter(|part| part.len() == len)
+                .and_then(|part| part.parse::<i64>().ok())
+        };
+        let (year, month, day) = (next(4)?, next(2)?, next(2)?);
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        // Counts from March so that the leap day comes last in the year.
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Some(era * 146097 + day_of_era - 719468)
+    }
+
+    /// Lowest possible value and highest plausible value of a factor.
+    fn bounds(&self, factor: ScoringFactor) -> (f64, f64) {
+        match factor {
+            ScoringFactor::OneMonthPriceChange
+            | ScoringFactor::ThreeMonthPriceChange
+            | ScoringFactor::SixMonthPriceChange
+            | ScoringFactor::TwelveMonthPriceChange => (-1.0, MAX_PLAUSIBLE_PRICE_CHANGE),
+            ScoringFactor::OneYearTotalReturn
+            | ScoringFactor::ThreeYearTotalReturn
+            | ScoringFactor::LongTermTotalReturn => (-1.0, MAX_PLAUSIBLE_TOTAL_RETURN),
+            ScoringFactor::AnnualizedVolatility => (0.0, MAX_PLAUSIBLE_VOLATILITY),
+            ScoringFactor::MaxDrawdown => (0.0, 1.0),
+            ScoringFactor::TrailingPe | ScoringFactor::ForwardPe => {
+                (f64::NEG_INFINITY, MAX_PLAUSIBLE_PE)
+            }
+            ScoringFactor::DividendYield => (0.0, MAX_PLAUSIBLE_DIVIDEND_YIELD),
+            ScoringFactor::EpsGrowth => (f64::NEG_INFINITY, MAX_PLAUSIBLE_PRICE_CHANGE),
+            ScoringFactor::ExpenseRatio => (0.0, MAX_PLAUSIBLE_EXPENSE_RATIO),
+            ScoringFactor::AssetsUnderManagement => (0.0, f64::INFINITY),
+            ScoringFactor::TrackingDifference => (-1.0, MAX_PLAUSIBLE_TRACKING_DIFFERENCE),
+        }
+    }
+
+    /// Only warns about a non-finite value, since ranking leaves out the factor rather than the
+    /// whole entry.
+    fn check_value(&self, value: f64, min: f64, max: f64) -> Option<(Severity, String)> {
+        if !value.is_finite() {
+            Some((
+                Severity::Warning,
+                format!("{} is not a finite number, so the factor is ignored", value),
+            ))
+        } else if value < min {
+            Some((
+                Severity::Error,
+                format!("{} is impossible, expected at least {}", value, min),
+            ))
+        } else if value > max {
+            Some((
+                Severity::Warning,
+                format!("{} is implausibly high, expected at most {}", value, max),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Fails if any issue prevents the run, which is only errors unless `strict`.
+    pub fn check(&self, issues: &[Issue], strict: bool) -> anyhow::Result<()> {
+        let error_count = issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .count();
+        let warning_count = issues.len() - error_count;
+        if error_count > 0 || (strict && warning_count > 0) {
+            anyhow::bail!(
+                "Found {} errors and {} warnings in the input{}",
+                error_count,
+                warning_count,
+                if strict { " in strict mode" } else { "" }
+            )
+        }
+        Ok(())
+    }
+
+    /// Renders one issue per line.
+    pub fn render(&self, issues: &[Issue]) -> String {
+        issues.iter().map(|issue| format!("{}\n", issue)).collect()
+    }
+
+    /// Drops the entries having errors.
+    pub fn filter(&self, metrics: Vec<ProductMetric>, issues: &[Issue]) -> Vec<ProductMetric> {
+        let rejected_indexes: HashSet<_> = issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.index)
+            .collect();
+        metrics
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !rejected_indexes.contains(index))
+            .map(|(_, metric)| metric)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::case;
+
+    fn metric(ticker: &str, one_month_price_change: f64) -> ProductMetric {
+        ProductMetric {
+            ticker: ticker.into(),
+            name: "Name".into(),
+            one_month_price_change: Some(one_month_price_change),
+            three_month_price_change: None,
+            six_month_price_change: None,
+            twelve_month_price_change: None,
+            one_year_total_return: None,
+            three_year_total_return: None,
+            long_term_total_return: None,
+            annualized_volatility: None,
+            max_drawdown: None,
+            trailing_pe: None,
+            forward_pe: None,
+            dividend_yield: None,
+            eps_growth: None,
+            expense_ratio: None,
+            assets_under_management: None,
+            tracking_difference: None,
+            asset_type: None,
+            exchange: None,
+            currency: None,
+            sector: None,
+            region: None,
+            as_of: None,
+            price: None,
+        }
+    }
+
+    #[test]
+    fn validate() {
+        // Given
+        let metrics = vec![
+            metric("A", 0.1),
+            metric("A", 0.1),
+            metric(" ", 0.1),
+            metric("B", 1e6),
+            metric("C", -1.5),
+            metric("D", f64::NAN),
+        ];
+
+        // When
+        let issues: Vec<_> = MetricValidator
+            .validate(&metrics)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        // Then
+        assert_eq!(
+            vec![
+                r#"error: [1].ticker ("A"): Duplicate of entry [0]"#,
+                r#"error: [2].ticker (" "): Ticker is empty"#,
+                r#"warning: [3].one_month_price_change ("B"): 1000000 is implausibly high, expected at most 10"#,
+                r#"error: [4].one_month_price_change ("C"): -1.5 is impossible, expected at least -1"#,
+                r#"warning: [5].one_month_price_change ("D"): NaN is not a finite number, so the factor is ignored"#,
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn validate_currency() {
+        // Given
+        let mut usd = metric("A", 0.1);
+        usd.currency = Some("USD".into());
+        let mut yen = metric("B", 0.1);
+        yen.currency = Some("円".into());
+
+        // When
+        let issues: Vec<_> = MetricValidator
+            .validate(&[usd, yen])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        // Then
+        assert_eq!(
+            vec![r#"warning: [1].currency ("B"): "円" is not an ISO 4217 code like "USD""#],
+            issues
+        );
+    }
+
+    #[case("1970-01-01T00:00:00Z" => Some(0) ; "Epoch")]
+    #[case("2024-03-01T09:30:00+09:00" => Some(19783) ; "After leap day")]
+    #[case("2026-10-18t05:55:08.123Z" => Some(20744) ; "Fraction of second")]
+    #[case("2026-13-01T00:00:00Z" => None ; "Invalid month")]
+    #[case("yesterday" => None ; "Not a date")]
+    fn parse_day(date_time: &str) -> Option<i64> {
+        MetricValidator.parse_day(date_time)
+    }
+
+    #[case("2026-10-18T05:55:08Z" => None ; "Fresh")]
+    #[case("2026-10-01T05:55:08Z" => Some("Collected 17 days ago, so the metrics are likely stale".into()) ; "Stale")]
+    #[case("last week" => Some(r#""last week" is not an RFC 3339 date-time"#.into()) ; "Malformed")]
+    fn check_as_of(as_of: &str) -> Option<String> {
+        MetricValidator.check_as_of(as_of, 20744)
+    }
+
+    #[test]
+    fn filter() {
+        // Given
+        let metrics = vec![
+            metric("A", 0.1),
+            metric("A", 0.2),
+            metric("B", 1e6),
+            metric("C", f64::INFINITY),
+        ];
+        let issues = MetricValidator.validate(&metrics);
+
+        // When
+        let actual: Vec<_> = MetricValidator
+            .filter(metrics, &issues)
+            .into_iter()
+            .map(|metric| (metric.ticker, metric.one_month_price_change))
+            .collect();
+
+        // Then
+        assert_eq!(
+            vec![
+                ("A".to_string(), Some(0.1)),
+                ("B".to_string(), Some(1e6)),
+                ("C".to_string(), Some(f64::INFINITY))
+            ],
+            actual
+        );
+    }
+
+    #[test]
+    fn check_warnings() {
+        // Given
+        let issues = MetricValidator.validate(&[metric("A", 0.1), metric("B", 1e6)]);
+
+        // Then
+        assert!(MetricValidator.check(&issues, false).is_ok());
+        assert!(MetricValidator.check(&issues, true).is_err());
+    }
+}